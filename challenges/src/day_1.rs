@@ -1,4 +1,4 @@
-use anyhow::Error;
+use anyhow::{Context, Error};
 use aoc_core::Lines;
 
 /// Day 1a: Report Repair (part 1)
@@ -46,39 +46,26 @@ use aoc_core::Lines;
 ///
 /// # Examples
 ///
-/// Input:
-///
-/// ```text
+/// ```example
+/// input:
 /// 1721
 /// 979
 /// 366
 /// 299
 /// 675
 /// 1456
-/// ```
-///
-/// Expected:
-///
-/// ```text
+/// expected:
 /// 514579
 /// ```
-#[aoc_macros::challenge]
+#[aoc_macros::challenge(
+    tags("math"),
+    line_oriented,
+    detect = "looks_like_input"
+)]
 pub fn part_1(text: Lines<u32>) -> Result<u32, Error> {
-    let values = text.0;
-
-    for i in 0..values.len() {
-        let first = values[i];
-
-        for j in i..values.len() {
-            let second = values[j];
-
-            if first + second == 2020 {
-                return Ok(first * second);
-            }
-        }
-    }
-
-    anyhow::bail!("Unable to find any results")
+    find_sum(&text, 2020, 2)
+        .map(|entries| entries.iter().product())
+        .context("Unable to find any results")
 }
 
 /// Day 1b: Report Repair (part 2)
@@ -95,25 +82,129 @@ pub fn part_1(text: Lines<u32>) -> Result<u32, Error> {
 ///
 /// In your expense report, what is the product of the three entries that sum to
 /// 2020?
-#[aoc_macros::challenge]
+///
+/// # Examples
+///
+/// ```example
+/// input:
+/// 1721
+/// 979
+/// 366
+/// 299
+/// 675
+/// 1456
+/// expected:
+/// 241861950
+/// ```
+#[aoc_macros::challenge(
+    tags("math"),
+    line_oriented,
+    detect = "looks_like_input",
+    progress = "part_2_with_progress"
+)]
 pub fn part_2(text: Lines<u32>) -> Result<u32, Error> {
-    let values = text.0;
+    find_sum(&text, 2020, 3)
+        .map(|entries| entries.iter().product())
+        .context("Unable to find any results")
+}
 
-    for i in 0..values.len() {
-        let first = values[i];
+/// The same as [`part_2()`], except progress is reported as each entry is
+/// tried as the first of the three.
+fn part_2_with_progress(
+    text: Lines<u32>,
+    progress: &mut dyn FnMut(f32),
+) -> Result<u32, Error> {
+    for (i, &first) in text.iter().enumerate() {
+        progress(i as f32 / text.len() as f32);
 
-        for j in i..values.len() {
-            let second = values[j];
+        if first > 2020 {
+            continue;
+        }
 
-            for k in j..values.len() {
-                let third = values[k];
+        if let Some(rest) = find_sum(&text[i + 1..], 2020 - first, 2) {
+            progress(1.0);
+            return Ok(first * rest.iter().product::<u32>());
+        }
+    }
 
-                if first + second + third == 2020 {
-                    return Ok(first * second * third);
-                }
-            }
+    anyhow::bail!("Unable to find any results")
+}
+
+/// Expense reports are a list of numbers, one per line.
+fn looks_like_input(input: &str) -> bool {
+    matches!(input.parse::<Lines<u32>>(), Ok(lines) if !lines.is_empty())
+}
+
+/// Find `count` distinct entries in `numbers` which add up to `target`,
+/// returning them in the order they appear.
+pub fn find_sum(
+    numbers: &[u32],
+    target: u32,
+    count: usize,
+) -> Option<Vec<u32>> {
+    if count == 0 {
+        return if target == 0 { Some(Vec::new()) } else { None };
+    }
+
+    for (i, &number) in numbers.iter().enumerate() {
+        if number > target {
+            continue;
         }
+
+        if let Some(mut rest) =
+            find_sum(&numbers[i + 1..], target - number, count - 1)
+        {
+            rest.insert(0, number);
+            return Some(rest);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &[u32] = &[1721, 979, 366, 299, 675, 1456];
+
+    #[test]
+    fn find_two_entries() {
+        let got = find_sum(EXAMPLE, 2020, 2).unwrap();
+
+        assert_eq!(got, &[1721, 299]);
     }
 
-    Err(Error::msg("Unable to find any results"))
+    #[test]
+    fn find_three_entries() {
+        let got = find_sum(EXAMPLE, 2020, 3).unwrap();
+
+        assert_eq!(got, &[979, 366, 675]);
+    }
+
+    #[test]
+    fn entries_cant_be_used_twice() {
+        assert_eq!(find_sum(&[1010, 5], 2020, 2), None);
+        assert_eq!(find_sum(&[1010, 5, 1010], 2020, 2), Some(vec![1010, 1010]));
+    }
+
+    #[test]
+    fn report_progress_while_solving_part_2() {
+        let mut updates = Vec::new();
+
+        let got =
+            part_2_with_progress(Lines(EXAMPLE.to_vec()), &mut |fraction| {
+                updates.push(fraction)
+            })
+            .unwrap();
+
+        assert_eq!(got, part_2(Lines(EXAMPLE.to_vec())).unwrap());
+        assert_eq!(updates, vec![0.0, 1.0 / 6.0, 1.0]);
+    }
+
+    #[test]
+    fn no_matching_entries() {
+        assert_eq!(find_sum(EXAMPLE, 1, 2), None);
+        assert_eq!(find_sum(EXAMPLE, 2020, 7), None);
+    }
 }
@@ -42,7 +42,22 @@ use std::str::FromStr;
 /// their respective policies.
 ///
 /// How many passwords are valid according to their policies?
-#[aoc_macros::challenge]
+///
+/// # Examples
+///
+/// ```example
+/// input:
+/// 1-3 a: abcde
+/// 1-3 b: cdefg
+/// 2-9 c: ccccccccc
+/// expected:
+/// 2
+/// ```
+#[aoc_macros::challenge(
+    tags("parsing"),
+    line_oriented,
+    detect = "looks_like_input"
+)]
 pub fn part_1(lines: Lines<Input>) -> Result<usize, Error> {
     Ok(lines
         .into_iter()
@@ -80,7 +95,22 @@ pub fn part_1(lines: Lines<Input>) -> Result<usize, Error> {
 ///
 /// How many passwords are valid according to the new interpretation of the
 /// policies?
-#[aoc_macros::challenge]
+///
+/// # Examples
+///
+/// ```example
+/// input:
+/// 1-3 a: abcde
+/// 1-3 b: cdefg
+/// 2-9 c: ccccccccc
+/// expected:
+/// 1
+/// ```
+#[aoc_macros::challenge(
+    tags("parsing"),
+    line_oriented,
+    detect = "looks_like_input"
+)]
 pub fn part_2(lines: Lines<Input>) -> Result<usize, Error> {
     Ok(lines
         .into_iter()
@@ -90,6 +120,11 @@ pub fn part_2(lines: Lines<Input>) -> Result<usize, Error> {
         .count())
 }
 
+/// The password database has a policy and password on each line.
+fn looks_like_input(input: &str) -> bool {
+    matches!(input.parse::<Lines<Input>>(), Ok(lines) if !lines.is_empty())
+}
+
 fn first_password_rule_is_valid(rule: Rule, password: &str) -> bool {
     let Rule {
         letter,
@@ -105,9 +140,16 @@ fn first_password_rule_is_valid(rule: Rule, password: &str) -> bool {
 fn second_password_rule_is_valid(rule: Rule, password: &str) -> bool {
     let Rule { letter, a, b } = rule;
 
-    let letters: Vec<_> = password.chars().collect();
+    // positions are 1-based, and anything outside the password (including 0)
+    // can't contain the letter
+    let letter_at = |position: usize| {
+        position
+            .checked_sub(1)
+            .and_then(|ix| password.chars().nth(ix))
+            == Some(letter)
+    };
 
-    (letters[a - 1] == letter) ^ (letters[b - 1] == letter)
+    letter_at(a) ^ letter_at(b)
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -120,18 +162,28 @@ impl FromStr for Input {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let colon = s.find(":").context(
-            "Expected the rule and password to be separated by a colon",
-        )?;
-        let (rule, password) = s.split_at(colon);
-        let password = &password[1..];
+        static PATTERN: Lazy<Regex> = Lazy::new(|| {
+            Regex::new(r"^\s*(\d+)-(\d+)\s*(\w)\s*:\s*(.*?)\s*$").unwrap()
+        });
+
+        let captures = PATTERN.captures(s).with_context(|| {
+            format!(r#"Expected a line like "2-15 x: password", found "{}""#, s)
+        })?;
+
+        let a = captures[1]
+            .parse()
+            .context("Couldn't parse the first value")?;
+        let b = captures[2]
+            .parse()
+            .context("Couldn't parse the second value")?;
+        let letter = captures[3]
+            .chars()
+            .next()
+            .expect("Regex guarantees exactly 1 letter");
 
         Ok(Input {
-            rule: rule
-                .trim()
-                .parse()
-                .context(r#"Rules should look like "2-15 x""#)?,
-            password: password.trim().to_string(),
+            rule: Rule { a, b, letter },
+            password: captures[4].to_string(),
         })
     }
 }
@@ -172,3 +224,93 @@ impl FromStr for Rule {
         Ok(Rule { a, b, letter })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_an_input_line() {
+        let got: Input = "2-15 x: xxyz ".parse().unwrap();
+
+        assert_eq!(
+            got,
+            Input {
+                rule: Rule {
+                    a: 2,
+                    b: 15,
+                    letter: 'x'
+                },
+                password: String::from("xxyz"),
+            }
+        );
+    }
+
+    #[test]
+    fn positions_past_the_end_of_the_password() {
+        let rule = Rule {
+            a: 5,
+            b: 9,
+            letter: 'x',
+        };
+        assert!(!second_password_rule_is_valid(rule, "ab"));
+        assert!(!second_password_rule_is_valid(rule, "xxx"));
+
+        let rule = Rule {
+            a: 1,
+            b: 9,
+            letter: 'x',
+        };
+        assert!(second_password_rule_is_valid(rule, "xb"));
+        assert!(!second_password_rule_is_valid(rule, ""));
+    }
+
+    #[test]
+    fn position_zero_never_matches() {
+        let rule = Rule {
+            a: 0,
+            b: 2,
+            letter: 'x',
+        };
+
+        assert!(second_password_rule_is_valid(rule, "xx"));
+        assert!(!second_password_rule_is_valid(rule, "xa"));
+    }
+
+    #[test]
+    fn malformed_lines_are_rejected() {
+        let inputs =
+            vec!["1-3 a abcde", "1-3: abcde", "1-3 ab: abcde", "a-3 b: c"];
+
+        for input in inputs {
+            let err = input.parse::<Input>().unwrap_err();
+
+            assert!(err.to_string().contains(input), "{}", err);
+        }
+    }
+
+    #[test]
+    fn parse_thousands_of_lines() {
+        let letters = "abcdefghijklmnopqrstuvwxyz";
+        let mut src = String::new();
+        let mut expected = Vec::new();
+
+        for i in 0..5000 {
+            let a = i % 7 + 1;
+            let b = a + i % 11;
+            let letter = letters.chars().nth(i % letters.len()).unwrap();
+            let password: String =
+                letters.chars().cycle().skip(i).take(b).collect();
+
+            src.push_str(&format!("{}-{} {}: {}\n", a, b, letter, password));
+            expected.push(Input {
+                rule: Rule { a, b, letter },
+                password,
+            });
+        }
+
+        let got: Lines<Input> = src.parse().unwrap();
+
+        assert_eq!(got.0, expected);
+    }
+}
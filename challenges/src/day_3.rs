@@ -1,10 +1,12 @@
 use std::{
     convert::TryFrom,
     fmt::{self, Display, Formatter},
+    ops::Deref,
     str::FromStr,
 };
 
 use anyhow::{Context, Error};
+use aoc_core::Grid;
 
 /// Day 3a: Toboggan Trajectory (part 1)
 ///
@@ -84,9 +86,28 @@ use anyhow::{Context, Error};
 ///
 /// Starting at the top-left corner of your map and following a slope of right 3
 /// and down 1, how many trees would you encounter?
-#[aoc_macros::challenge]
+///
+/// # Examples
+///
+/// ```example
+/// input:
+/// ..##.......
+/// #...#...#..
+/// .#....#..#.
+/// ..#.#...#.#
+/// .#...##..#.
+/// ..#.##.....
+/// .#.#.#....#
+/// .#........#
+/// #.##...#...
+/// #...##....#
+/// .#..#...#.#
+/// expected:
+/// 7
+/// ```
+#[aoc_macros::challenge(tags("grid"), bytes, detect = "looks_like_input")]
 pub fn part_1(board: Board) -> Result<usize, Error> {
-    Ok(trees_along_slope(&board, 3, 1))
+    Ok(board.trees_along_slope(3, 1))
 }
 
 /// Day 3b: Toboggan Trajectory (part 2)
@@ -113,95 +134,202 @@ pub fn part_1(board: Board) -> Result<usize, Error> {
 ///
 /// What do you get if you multiply together the number of trees encountered on
 /// each of the listed slopes?
-#[aoc_macros::challenge]
-pub fn part_2(board: Board) -> Result<usize, Error> {
+///
+/// # Examples
+///
+/// ```example
+/// input:
+/// ..##.......
+/// #...#...#..
+/// .#....#..#.
+/// ..#.#...#.#
+/// .#...##..#.
+/// ..#.##.....
+/// .#.#.#....#
+/// .#........#
+/// #.##...#...
+/// #...##....#
+/// .#..#...#.#
+/// expected:
+/// 336
+/// ```
+#[aoc_macros::challenge(tags("grid"), bytes, detect = "looks_like_input")]
+pub fn part_2(board: Board) -> Result<u64, Error> {
     let combinations = &[(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
 
-    Ok(combinations
-        .iter()
-        .copied()
-        .map(|(horizontal, vertical)| {
-            trees_along_slope(&board, horizontal, vertical)
-        })
-        .product())
+    checked_product(
+        combinations
+            .iter()
+            .map(|&(right, down)| board.trees_along_slope(right, down)),
+    )
 }
 
-fn trees_along_slope(
-    board: &Board,
-    horizontal_delta: usize,
-    vertical_delta: usize,
-) -> usize {
-    let mut row = 0;
-    let mut column = 0;
-    let mut trees = 0;
-
-    while row < board.height {
-        let tile = board.tile_at(column, row);
-
-        if tile == Tile::Tree {
-            trees += 1;
-        }
+/// Maps are a grid of `#` and `.` characters.
+fn looks_like_input(input: &str) -> bool {
+    Board::try_from(input).is_ok()
+}
 
-        row += vertical_delta;
-        column += horizontal_delta;
+/// Multiply the tree counts together, returning an error instead of silently
+/// wrapping if the result doesn't fit in a `u64`.
+fn checked_product<I>(counts: I) -> Result<u64, Error>
+where
+    I: IntoIterator<Item = usize>,
+{
+    let mut product: u64 = 1;
+
+    for count in counts {
+        product = u64::try_from(count)
+            .ok()
+            .and_then(|count| product.checked_mul(count))
+            .with_context(|| {
+                format!(
+                    "Multiplying {} by {} trees would overflow",
+                    product, count
+                )
+            })?;
     }
 
-    trees
+    Ok(product)
 }
 
+/// The toboggan's map, a [`Grid`] of [`Tile`]s which repeats to the right.
+///
+/// General-purpose helpers (e.g. [`Grid::neighbors()`] or
+/// [`Grid::flood_fill()`]) are available through `Deref`.
 #[derive(Debug, Default, Clone, PartialEq)]
-pub struct Board {
-    tiles: Vec<Tile>,
-    width: usize,
-    height: usize,
-}
+pub struct Board(pub Grid<Tile>);
 
 impl Board {
     pub fn new(width: usize, height: usize, tiles: Vec<Tile>) -> Self {
-        assert_eq!(width * height, tiles.len());
+        Board(Grid::new(width, height, tiles))
+    }
 
-        Board {
-            width,
-            height,
-            tiles,
-        }
+    /// Create a board from its rows, making sure they all have the same
+    /// length.
+    pub fn from_rows(rows: Vec<Vec<Tile>>) -> Result<Board, Error> {
+        Grid::from_rows(rows).map(Board)
     }
 
+    /// Get a tile, wrapping around horizontally because the toboggan's map
+    /// repeats to the right.
     pub fn tile_at(&self, column: usize, row: usize) -> Tile {
-        let ix = self.index(column % self.width, row);
-        self.tiles[ix]
+        let width = self.width();
+        self.get_checked(column % width, row).unwrap_or_else(|| {
+            panic!("Row {} is past the bottom of the board", row)
+        })
+    }
+
+    /// Get a tile, returning `None` instead of wrapping around or panicking
+    /// if the location is off the board.
+    pub fn get_checked(&self, column: usize, row: usize) -> Option<Tile> {
+        self.0.get(column, row).copied()
+    }
+
+    /// Count the trees you would hit when starting at the top-left corner and
+    /// repeatedly moving `right` columns and `down` rows until you go past the
+    /// bottom of the board.
+    ///
+    /// A slope which never moves `down` would never leave the board, so it
+    /// doesn't hit any trees.
+    pub fn trees_along_slope(&self, right: usize, down: usize) -> usize {
+        if down == 0 {
+            return 0;
+        }
+
+        let width = self.width();
+        let mut row = 0;
+        let mut column = 0;
+        let mut trees = 0;
+
+        while row < self.height() {
+            let tile = self.tile_at(column, row);
+
+            if tile == Tile::Tree {
+                trees += 1;
+            }
+
+            row += down;
+            // wrap early so steep slopes on tall boards can't overflow
+            column = (column + right % width) % width;
+        }
+
+        trees
     }
 
-    pub fn rows(&self) -> impl Iterator<Item = &[Tile]> + '_ {
-        let Board {
-            ref tiles,
-            width,
-            height,
-        } = *self;
+    /// The fraction of tiles visited by [`Board::trees_along_slope()`] which
+    /// are trees.
+    ///
+    /// A slope which goes `down` past the bottom of the board in one step
+    /// only visits the top-left tile, and a slope which never moves `down`
+    /// doesn't visit any tiles so its density is `0.0`.
+    pub fn tree_density(&self, right: usize, down: usize) -> f64 {
+        if down == 0 || self.height() == 0 {
+            return 0.0;
+        }
+
+        let visited = self.height().div_ceil(down);
 
-        (0..height)
-            .map(move |row| row * width)
-            .map(move |first_index| &tiles[first_index..first_index + width])
+        self.trees_along_slope(right, down) as f64 / visited as f64
     }
 
-    fn index(&self, column: usize, row: usize) -> usize {
-        column + row * self.width
+    /// Count the tiles matching a predicate.
+    pub fn count_tiles(&self, predicate: impl Fn(Tile) -> bool) -> usize {
+        self.count_cells(predicate)
     }
-}
 
-impl Display for Board {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    /// Rotate the board 90 degrees clockwise.
+    pub fn rotate90(&self) -> Board {
+        Board(self.0.rotate90())
+    }
+
+    /// Rotate the board 180 degrees.
+    pub fn rotate180(&self) -> Board {
+        Board(self.0.rotate180())
+    }
+
+    /// Rotate the board 270 degrees clockwise (i.e. 90 degrees
+    /// anticlockwise).
+    pub fn rotate270(&self) -> Board {
+        Board(self.0.rotate270())
+    }
+
+    /// Flip the board along its main diagonal, so rows become columns.
+    pub fn transpose(&self) -> Board {
+        Board(self.0.transpose())
+    }
+
+    /// Draw the board using `#` for trees and `.` for open tiles, or a space
+    /// for open tiles when `show_open` is `false` so only the trees stand out.
+    pub fn render(&self, show_open: bool) -> String {
+        let open = if show_open { '.' } else { ' ' };
+        let mut rendered =
+            String::with_capacity((self.width() + 1) * self.height());
+
         for row in self.rows() {
             for tile in row {
                 match tile {
-                    Tile::Tree => write!(f, "#")?,
-                    Tile::Open => write!(f, ".")?,
+                    Tile::Tree => rendered.push('#'),
+                    Tile::Open => rendered.push(open),
                 }
             }
-            writeln!(f)?;
+            rendered.push('\n');
         }
 
-        Ok(())
+        rendered
+    }
+}
+
+impl Deref for Board {
+    type Target = Grid<Tile>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(true))
     }
 }
 
@@ -217,7 +345,20 @@ impl<'input> TryFrom<&'input str> for Board {
     type Error = Error;
 
     fn try_from(s: &'input str) -> Result<Self, Self::Error> {
-        let mut lines = s.lines().filter(|l| !l.is_empty());
+        Board::try_from(s.as_bytes())
+    }
+}
+
+/// Boards only contain ASCII, so they can be parsed directly from bytes
+/// without needing to check for valid UTF-8 first.
+impl<'input> TryFrom<&'input [u8]> for Board {
+    type Error = Error;
+
+    fn try_from(s: &'input [u8]) -> Result<Self, Self::Error> {
+        let mut lines = s
+            .split(|&b| b == b'\n')
+            .map(trim_ascii_whitespace)
+            .filter(|l| !l.is_empty());
 
         let mut tiles = Vec::new();
 
@@ -242,24 +383,30 @@ impl<'input> TryFrom<&'input str> for Board {
             }
         }
 
-        Ok(Board {
-            tiles,
-            width,
-            height,
-        })
+        Ok(Board::new(width, height, tiles))
     }
 }
 
-fn append_tiles(dest: &mut Vec<Tile>, line: &str) -> Result<(), Error> {
-    for letter in line.trim().chars() {
-        match letter {
-            '#' => dest.push(Tile::Tree),
-            '.' => dest.push(Tile::Open),
-            other => anyhow::bail!(
-                "The board can only contain \"#\" or \".\", found \"{}\"",
-                other
-            ),
-        }
+fn trim_ascii_whitespace(line: &[u8]) -> &[u8] {
+    let start = line
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(line.len());
+    let end = line
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(start, |ix| ix + 1);
+
+    &line[start..end]
+}
+
+fn append_tiles(dest: &mut Vec<Tile>, line: &[u8]) -> Result<(), Error> {
+    for &byte in line {
+        anyhow::ensure!(
+            byte.is_ascii(),
+            "The board can only contain \"#\" or \".\", found a non-ASCII character"
+        );
+        dest.push(Tile::try_from(char::from(byte))?);
     }
 
     Ok(())
@@ -271,6 +418,37 @@ pub enum Tile {
     Tree,
 }
 
+impl TryFrom<char> for Tile {
+    type Error = Error;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '#' => Ok(Tile::Tree),
+            '.' => Ok(Tile::Open),
+            other => anyhow::bail!(
+                "The board can only contain \"#\" or \".\", found \"{}\"",
+                other
+            ),
+        }
+    }
+}
+
+impl FromStr for Tile {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Tile::try_from(c),
+            _ => anyhow::bail!(
+                "Expected a single \"#\" or \".\", found \"{}\"",
+                s
+            ),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -294,9 +472,9 @@ mod tests {
 
         let got: Board = raw.parse().unwrap();
 
-        assert_eq!(got.width, 11);
-        assert_eq!(got.height, 11);
-        assert_eq!(got.height, got.rows().count());
+        assert_eq!(got.width(), 11);
+        assert_eq!(got.height(), 11);
+        assert_eq!(got.height(), got.rows().count());
         let second_row_should_be = &[
             Tile::Tree,
             Tile::Open,
@@ -313,6 +491,288 @@ mod tests {
         assert_eq!(got.rows().nth(1).unwrap(), second_row_should_be);
     }
 
+    #[test]
+    fn parse_a_large_board_from_bytes() {
+        let width = 1000;
+        let board: String = (0..2000)
+            .map(|row| {
+                let is_tree = |column| (row * column) % 7 == 3;
+                let mut line: String = (0..width)
+                    .map(|column| if is_tree(column) { '#' } else { '.' })
+                    .collect();
+                line.push_str("\r\n");
+                line
+            })
+            .collect();
+
+        let from_bytes = Board::try_from(board.as_bytes()).unwrap();
+
+        assert_eq!(from_bytes, Board::from_str(&board).unwrap());
+        assert_eq!((from_bytes.width(), from_bytes.height()), (width, 2000));
+        for number in &["3a", "3b"] {
+            let challenge = aoc_core::all_challenges()
+                .find(|c| c.number == *number)
+                .unwrap();
+            assert!(challenge.accepts_bytes);
+            assert_eq!(
+                challenge.run_bytes(board.as_bytes()).unwrap(),
+                challenge.run(&board).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn convert_characters_to_tiles() {
+        assert_eq!(Tile::try_from('#').unwrap(), Tile::Tree);
+        assert_eq!(Tile::try_from('.').unwrap(), Tile::Open);
+        assert_eq!("#".parse::<Tile>().unwrap(), Tile::Tree);
+
+        let err = Tile::try_from('x').unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The board can only contain \"#\" or \".\", found \"x\""
+        );
+        assert!("".parse::<Tile>().is_err());
+        assert!("##".parse::<Tile>().is_err());
+        assert!("x".parse::<Tile>().is_err());
+    }
+
+    #[test]
+    fn iterate_over_columns() {
+        let board = Board::from_str(EXAMPLE_BOARD).unwrap();
+
+        let first_column: Vec<_> = board.column(0).collect();
+
+        let should_be: Vec<_> = EXAMPLE_BOARD
+            .trim()
+            .lines()
+            .map(|line| match line.as_bytes()[0] {
+                b'#' => Tile::Tree,
+                _ => Tile::Open,
+            })
+            .collect();
+        assert_eq!(first_column, should_be);
+        assert_eq!(first_column[1], Tile::Tree);
+        assert_eq!(board.column(board.width()).count(), 0);
+        assert_eq!(board.columns().count(), board.width());
+        assert_eq!(board.columns().next().unwrap(), first_column);
+        assert_eq!(
+            board.columns().collect::<Vec<_>>(),
+            board
+                .transpose()
+                .rows()
+                .map(<[Tile]>::to_vec)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn render_only_the_trees() {
+        let board = Board::from_str(EXAMPLE_BOARD).unwrap();
+
+        let everything = board.render(true);
+        let trees_only = board.render(false);
+
+        assert_eq!(everything, board.to_string());
+        assert_eq!(everything.trim(), EXAMPLE_BOARD.trim());
+        assert_eq!(trees_only, everything.replace('.', " "));
+        assert!(trees_only.lines().next().unwrap().starts_with("  ##  "));
+    }
+
+    #[test]
+    fn count_trees_along_custom_slopes() {
+        let board = Board::from_str(EXAMPLE_BOARD).unwrap();
+
+        assert_eq!(board.trees_along_slope(3, 1), 7);
+        assert_eq!(board.trees_along_slope(1, 2), 2);
+        assert_eq!(board.trees_along_slope(7, 2), 1);
+    }
+
+    #[test]
+    fn slopes_which_never_go_down_hit_no_trees() {
+        let board = Board::from_str(EXAMPLE_BOARD).unwrap();
+
+        assert_eq!(board.trees_along_slope(3, 0), 0);
+        assert_eq!(board.trees_along_slope(0, 0), 0);
+    }
+
+    #[test]
+    fn multiplying_tree_counts_checks_for_overflow() {
+        let big = u32::MAX as usize;
+
+        assert_eq!(
+            checked_product(vec![big, big]).unwrap(),
+            u64::from(u32::MAX) * u64::from(u32::MAX),
+        );
+        assert!(checked_product(vec![big, big, 2]).is_err());
+        assert_eq!(checked_product(vec![]).unwrap(), 1);
+    }
+
+    #[test]
+    fn tree_density_along_a_slope() {
+        let board = Board::from_str(EXAMPLE_BOARD).unwrap();
+
+        assert_eq!(board.tree_density(3, 1), 7.0 / 11.0);
+        assert_eq!(board.tree_density(1, 2), 2.0 / 6.0);
+        assert_eq!(board.tree_density(1, 11), 0.0);
+        assert_eq!(board.tree_density(1, 100), 0.0);
+        assert_eq!(board.tree_density(1, 0), 0.0);
+
+        // steep slopes still visit the top-left tile
+        let board = Board::from_str("#.\n..").unwrap();
+        assert_eq!(board.trees_along_slope(1, 5), 1);
+        assert_eq!(board.tree_density(1, 5), 1.0);
+    }
+
+    #[test]
+    fn steep_slopes_wrap_around() {
+        let board = Board::from_str(EXAMPLE_BOARD).unwrap();
+
+        assert_eq!(
+            board.trees_along_slope(usize::MAX, 1),
+            board.trees_along_slope(usize::MAX % board.width(), 1),
+        );
+    }
+
+    #[test]
+    fn neighbors_dont_wrap() {
+        let (t, o) = (Tile::Tree, Tile::Open);
+        let board =
+            Board::from_rows(vec![vec![t, o, t], vec![o, o, t], vec![t, o, o]])
+                .unwrap();
+
+        let corner: Vec<_> = board.neighbors(0, 0).collect();
+        assert_eq!(
+            corner,
+            &[(1, 0, Tile::Open), (0, 1, Tile::Open), (1, 1, Tile::Open)]
+        );
+
+        let centre: Vec<_> = board.neighbors(1, 1).collect();
+        assert_eq!(centre.len(), 8);
+        assert_eq!(centre.iter().filter(|n| n.2 == Tile::Tree).count(), 4);
+
+        let edge: Vec<_> = board.orthogonal_neighbors(2, 1).collect();
+        assert_eq!(
+            edge,
+            &[(2, 0, Tile::Tree), (1, 1, Tile::Open), (2, 2, Tile::Open)]
+        );
+
+        assert_eq!(board.orthogonal_neighbors(1, 1).count(), 4);
+    }
+
+    #[test]
+    fn rows_must_all_be_the_same_length() {
+        let (t, o) = (Tile::Tree, Tile::Open);
+
+        let err = Board::from_rows(vec![vec![t, o], vec![o], vec![t, t]])
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "The grid should be 2 items wide but row 2 had 1 items"
+        );
+        assert!(Board::from_rows(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn build_a_board_from_rows() {
+        let (t, o) = (Tile::Tree, Tile::Open);
+
+        let got = Board::from_rows(vec![vec![t, o, o], vec![o, o, t]]).unwrap();
+
+        assert_eq!(got, Board::from_str("#..\n..#").unwrap());
+    }
+
+    #[test]
+    fn flood_fill_a_single_region() {
+        let board = Board::from_str("..#..\n.##..\n#..#.\n..#..").unwrap();
+        let is_open = |tile| tile == Tile::Open;
+
+        let mut got = board.flood_fill((0, 0), is_open);
+        got.sort();
+
+        assert_eq!(got, &[(0, 0), (0, 1), (1, 0)]);
+        let other_region = board.flood_fill((4, 0), is_open);
+        assert_eq!(other_region.len(), 7);
+        assert_eq!(other_region[0], (4, 0));
+        assert!(!other_region.contains(&(0, 0)));
+        assert!(board.flood_fill((2, 0), is_open).is_empty());
+        assert!(board.flood_fill((5, 0), is_open).is_empty());
+    }
+
+    #[test]
+    fn count_trees_with_a_predicate() {
+        let board = Board::from_str(EXAMPLE_BOARD).unwrap();
+        let manual_count = EXAMPLE_BOARD.matches('#').count();
+
+        let got = board.count_tiles(|tile| tile == Tile::Tree);
+
+        assert_eq!(got, manual_count);
+        assert_eq!(
+            board.count_tiles(|tile| tile == Tile::Open),
+            board.width() * board.height() - manual_count
+        );
+    }
+
+    #[test]
+    fn positions_are_row_major() {
+        let board = Board::from_str("#.\n.#\n..").unwrap();
+
+        let got: Vec<_> = board.positions().collect();
+
+        assert_eq!(got.len(), 6);
+        assert_eq!(got[0], (0, 0, Tile::Tree));
+        assert_eq!(got[1], (1, 0, Tile::Open));
+        assert_eq!(got[3], (1, 1, Tile::Tree));
+        assert_eq!(got[5], (1, 2, Tile::Open));
+        assert!(got
+            .iter()
+            .all(|&(column, row, tile)| board.tile_at(column, row) == tile));
+    }
+
+    #[test]
+    fn rotate_a_rectangular_board() {
+        let board = Board::from_str("#..\n..#").unwrap();
+
+        let got = board.rotate90();
+
+        assert_eq!((got.width(), got.height()), (2, 3));
+        assert_eq!(got.to_string(), ".#\n..\n#.\n");
+        assert_eq!(board.rotate180().to_string(), "#..\n..#\n");
+        assert_eq!(board.rotate270().to_string(), ".#\n..\n#.\n");
+        assert_eq!(board.transpose().to_string(), "#.\n..\n.#\n");
+    }
+
+    #[test]
+    fn rotating_four_times_gives_the_original_board() {
+        let first_four_rows: Vec<_> =
+            EXAMPLE_BOARD.trim().lines().take(4).collect();
+        let board = Board::from_str(&first_four_rows.join("\n")).unwrap();
+        assert_eq!((board.width(), board.height()), (11, 4));
+
+        let rotated = board.rotate90().rotate90().rotate90().rotate90();
+
+        assert_eq!(rotated, board);
+        assert_eq!(board.rotate90().rotate90(), board.rotate180());
+        assert_eq!(board.rotate90().rotate180(), board.rotate270());
+        assert_eq!(board.transpose().transpose(), board);
+    }
+
+    #[test]
+    fn checked_access_doesnt_wrap() {
+        let board = Board::from_str("#..\n..#").unwrap();
+
+        assert_eq!(board.get_checked(0, 0), Some(Tile::Tree));
+        assert_eq!(board.get_checked(1, 1), Some(Tile::Open));
+        assert_eq!(board.get_checked(2, 1), Some(Tile::Tree));
+        // out of bounds rows
+        assert_eq!(board.get_checked(0, 2), None);
+        assert_eq!(board.get_checked(0, usize::MAX), None);
+        // out of bounds columns, which tile_at() would wrap
+        assert_eq!(board.get_checked(3, 0), None);
+        assert_eq!(board.tile_at(3, 0), Tile::Tree);
+    }
+
     #[test]
     fn wrap_horizontally() {
         let board = Board::from_str(EXAMPLE_BOARD).unwrap();
@@ -332,15 +792,15 @@ mod tests {
         let row = 1;
 
         // iterate through in the normal range
-        for column in 0..board.width {
+        for (column, &should_be) in second_row.iter().enumerate() {
             let got = board.tile_at(column, row);
-            assert_eq!(got, second_row[column]);
+            assert_eq!(got, should_be);
         }
 
         // and then wrap around to the right
-        for column in board.width..2 * board.width {
+        for column in board.width()..2 * board.width() {
             let got = board.tile_at(column, row);
-            assert_eq!(got, second_row[column - board.width]);
+            assert_eq!(got, second_row[column - board.width()]);
         }
     }
 }
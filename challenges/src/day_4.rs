@@ -1,8 +1,14 @@
-use std::{borrow::Borrow, convert::TryInto, hash::Hash, iter::FromIterator};
-use std::{collections::HashMap, convert::TryFrom, ops::Deref, str::FromStr};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+    iter::FromIterator,
+    ops::Deref,
+    str::FromStr,
+};
 
 use anyhow::{Context, Error};
-use aoc_core::GroupedLines;
+use aoc_core::{Group, GroupedLines};
 
 /// Day 4a: Passport Processing
 ///
@@ -73,18 +79,57 @@ use aoc_core::GroupedLines;
 ///
 /// Count the number of valid passports - those that have all required fields.
 /// Treat cid as optional. In your batch file, how many passports are valid?
-#[aoc_macros::challenge]
+///
+/// # Examples
+///
+/// ```example
+/// input:
+/// ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+/// byr:1937 iyr:2017 cid:147 hgt:183cm
+///
+/// iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
+/// hcl:#cfa07d byr:1929
+///
+/// hcl:#ae17e1 iyr:2013
+/// eyr:2024
+/// ecl:brn pid:760753108 byr:1931
+/// hgt:179cm
+///
+/// hcl:#cfa07d eyr:2025 pid:166559648
+/// iyr:2011 ecl:brn hgt:59in
+/// expected:
+/// 2
+/// ```
+#[aoc_macros::challenge(tags("parsing"), detect = "looks_like_input")]
 pub fn part_1(passports: Passports<'_>) -> Result<usize, Error> {
-    let required_fields = &["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"];
-
-    Ok(passports
-        .iter()
-        .filter(|p| {
-            required_fields
-                .iter()
-                .all(|field_name| p.contains_key(field_name))
-        })
-        .count())
+    Ok(FieldPolicy::default_2020().count_present(&passports))
+}
+
+/// The fields a passport must have to be considered valid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldPolicy {
+    pub required: Vec<&'static str>,
+}
+
+impl FieldPolicy {
+    /// The policy from the 2020 puzzle, where every field except `cid` is
+    /// required.
+    pub fn default_2020() -> Self {
+        FieldPolicy {
+            required: vec!["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid"],
+        }
+    }
+
+    pub fn is_satisfied_by(&self, passport: &Passport<'_>) -> bool {
+        self.required
+            .iter()
+            .all(|field_name| passport.contains_key(field_name))
+    }
+
+    /// Count the passports which have all the required fields.
+    pub fn count_present(&self, passports: &Passports<'_>) -> usize {
+        passports.iter().filter(|p| self.is_satisfied_by(p)).count()
+    }
 }
 
 /// Day 4b: Passport Processing (part 2)
@@ -172,12 +217,62 @@ pub fn part_1(passports: Passports<'_>) -> Result<usize, Error> {
 /// Count the number of valid passports - those that have all required fields and
 /// valid values. Continue to treat cid as optional. In your batch file, how many
 /// passports are valid?
-#[aoc_macros::challenge]
+///
+/// # Examples
+///
+/// ```example
+/// input:
+/// eyr:1972 cid:100
+/// hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926
+///
+/// iyr:2019
+/// hcl:#602927 eyr:1967 hgt:170cm
+/// ecl:grn pid:012533040 byr:1946
+///
+/// hcl:dab227 iyr:2012
+/// ecl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277
+///
+/// hgt:59cm ecl:zzz
+/// eyr:2038 hcl:74454a iyr:2023
+/// pid:3556412378 byr:2007
+/// expected:
+/// 0
+/// ```
+///
+/// ```example
+/// input:
+/// pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980
+/// hcl:#623a2f
+///
+/// eyr:2029 ecl:blu cid:129 byr:1989
+/// iyr:2014 pid:896056539 hcl:#a97842 hgt:165cm
+///
+/// hcl:#888785
+/// hgt:164cm byr:2001 iyr:2015 cid:88
+/// pid:545766238 ecl:hzl
+/// eyr:2022
+///
+/// iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719
+/// expected:
+/// 4
+/// ```
+#[aoc_macros::challenge(tags("parsing"), detect = "looks_like_input")]
 pub fn part_2(passports: Passports<'_>) -> Result<usize, Error> {
-    Ok(passports.iter().filter(|p| is_valid(p)).count())
+    Ok(passports.iter().filter(|p| validate(p).is_ok()).count())
 }
 
-fn is_valid(passport: &Passport<'_>) -> bool {
+/// Passports are made up of whitespace-separated `key:value` fields.
+fn looks_like_input(input: &str) -> bool {
+    let mut fields = input.split_whitespace().peekable();
+
+    fields.peek().is_some()
+        && fields.all(|field| match field.split_once(':') {
+            Some((key, value)) => !key.is_empty() && !value.is_empty(),
+            None => false,
+        })
+}
+
+fn validate(passport: &Passport<'_>) -> Result<(), ValidationError> {
     // byr (Birth Year) - four digits; at least 1920 and at most 2002.
     // iyr (Issue Year) - four digits; at least 2010 and at most 2020.
     // eyr (Expiration Year) - four digits; at least 2020 and at most 2030.
@@ -189,54 +284,104 @@ fn is_valid(passport: &Passport<'_>) -> bool {
     // pid (Passport ID) - a nine-digit number, including leading zeroes.
     // cid (Country ID) - ignored, missing or not.
 
-    // Note: This was massively over-engineered, using a pseudo-monad approach
-
-    true && check(&passport.fields)
-        .and_then(require_key("byr"))
-        .and_then(is_digit)
-        .and_then(between(1920, 2002))
-        .is_some()
-        && check(&passport.fields)
-            .and_then(require_key("iyr"))
-            .and_then(is_digit)
-            .and_then(between(2010, 2020))
-            .is_some()
-        && check(&passport.fields)
-            .and_then(require_key("eyr"))
-            .and_then(is_digit)
-            .and_then(between(2020, 2030))
-            .is_some()
-        && check(&passport.fields)
-            .and_then(require_key("hgt"))
-            .and_then(parse::<Height, _>)
-            .and_then(validate_height)
-            .is_some()
-        && check(&passport.fields)
-            .and_then(require_key("hcl"))
-            .and_then(parse::<Colour, _>)
-            .is_some()
-        && check(&passport.fields)
-            .and_then(require_key("ecl"))
-            .copied()
-            .and_then(is_one_of::<&str, _>([
-                "amb", "blu", "brn", "gry", "grn", "hzl", "oth",
-            ]))
-            .is_some()
-        && check(&passport.fields)
-            .and_then(require_key("pid"))
-            .and_then(decimal_number_with_length(9))
-            .is_some()
+    validate_year(passport, "byr", 1920, 2002)?;
+    validate_year(passport, "iyr", 2010, 2020)?;
+    validate_year(passport, "eyr", 2020, 2030)?;
+
+    let height = required(passport, "hgt")?;
+    let height = parse::<Height, _>(height)
+        .ok_or(ValidationError::WrongFormat("hgt"))?;
+    validate_height(height).ok_or(ValidationError::OutOfRange("hgt"))?;
+
+    let hair_colour = required(passport, "hcl")?;
+    parse::<Colour, _>(hair_colour)
+        .ok_or(ValidationError::WrongFormat("hcl"))?;
+
+    let eye_colour = required(passport, "ecl")?;
+    is_one_of(["amb", "blu", "brn", "gry", "grn", "hzl", "oth"])(eye_colour)
+        .ok_or(ValidationError::WrongFormat("ecl"))?;
+
+    let passport_id = required(passport, "pid")?;
+    decimal_number_with_length(9)(passport_id)
+        .ok_or(ValidationError::WrongFormat("pid"))?;
+
+    Ok(())
+}
+
+fn required<'input>(
+    passport: &Passport<'input>,
+    field: &'static str,
+) -> Result<&'input str, ValidationError> {
+    passport
+        .get(field)
+        .copied()
+        .ok_or(ValidationError::Missing(field))
+}
+
+fn validate_year(
+    passport: &Passport<'_>,
+    field: &'static str,
+    min: u32,
+    max: u32,
+) -> Result<(), ValidationError> {
+    let year = is_digit(required(passport, field)?)
+        .ok_or(ValidationError::WrongFormat(field))?;
+
+    between(min, max)(year).ok_or(ValidationError::OutOfRange(field))
+}
+
+/// The reason a passport failed validation, and the field responsible.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ValidationError {
+    Missing(&'static str),
+    OutOfRange(&'static str),
+    WrongFormat(&'static str),
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::Missing(field) => {
+                write!(f, "The \"{}\" field is missing", field)
+            },
+            ValidationError::OutOfRange(field) => {
+                write!(f, "The \"{}\" field is out of range", field)
+            },
+            ValidationError::WrongFormat(field) => {
+                write!(f, "The \"{}\" field has the wrong format", field)
+            },
+        }
+    }
 }
 
-pub struct Colour(u32);
+impl std::error::Error for ValidationError {}
+
+pub struct Colour(pub u32);
 
 impl FromStr for Colour {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        anyhow::ensure!(s.starts_with("#"));
-        let number = &s[1..];
-        anyhow::ensure!(number.len() == 6);
+        let number = s.strip_prefix('#').with_context(|| {
+            format!("Hex colours must start with \"#\", found \"{}\"", s)
+        })?;
+
+        // from_str_radix() would also accept uppercase letters
+        if let Some(bad) =
+            number.chars().find(|c| !matches!(c, '0'..='9' | 'a'..='f'))
+        {
+            anyhow::bail!(
+                "Hex colours may only contain 0-9 and a-f, found \"{}\"",
+                bad
+            );
+        }
+
+        anyhow::ensure!(
+            number.len() == 6,
+            "Hex colours must be exactly 6 digits, found {} in \"{}\"",
+            number.len(),
+            s
+        );
 
         let hex = u32::from_str_radix(number, 16)?;
 
@@ -244,11 +389,34 @@ impl FromStr for Colour {
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Height {
     Centimeters(u32),
     Inches(u32),
 }
 
+impl Height {
+    /// The height, in whatever units it was measured in.
+    pub fn value(&self) -> u32 {
+        match *self {
+            Height::Centimeters(value) | Height::Inches(value) => value,
+        }
+    }
+
+    pub fn is_metric(&self) -> bool {
+        matches!(self, Height::Centimeters(_))
+    }
+}
+
+impl Display for Height {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Height::Centimeters(value) => write!(f, "{}cm", value),
+            Height::Inches(value) => write!(f, "{}in", value),
+        }
+    }
+}
+
 impl FromStr for Height {
     type Err = Error;
 
@@ -260,21 +428,27 @@ impl FromStr for Height {
             [start @ .., b'i', b'n'] => {
                 (start, Height::Inches as fn(u32) -> Height)
             }
-            _ => {
-                return Err(Error::msg(
-                    "Expected a height like \"150cm\" or \"90in\"",
-                ));
-            }
+            _ => anyhow::bail!(
+                "Heights must end in \"cm\" or \"in\" (e.g. \"150cm\" or \"90in\"), found \"{}\"",
+                s
+            ),
         };
 
         let number = std::str::from_utf8(number)
             .expect("Guaranteed to be valid")
-            .parse()?;
+            .parse()
+            .with_context(|| {
+                format!("The height must be a whole number, found \"{}\"", s)
+            })?;
 
         Ok(f(number))
     }
 }
 
+/// A batch of passports.
+///
+/// Each [`Passport`] borrows its fields from the original input, so this can
+/// only be parsed using `TryFrom<&str>` and not [`FromStr`].
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct Passports<'input>(Vec<Passport<'input>>);
 
@@ -290,31 +464,12 @@ impl<'input> TryFrom<&'input str> for Passports<'input> {
     type Error = Error;
 
     fn try_from(value: &'input str) -> Result<Self, Self::Error> {
-        let groups: GroupedLines = value.try_into()?;
-        let mut line_number = 0;
-
-        groups
-            .into_iter()
-            .map(|group| {
-                line_number += 1;
-                let mut passport = Passport::default();
-
-                for line in group {
-                    for pair in line.split_whitespace() {
-                        let colon = pair.find(":").with_context(|| {
-                            format!(
-                            "Expected \"{}\" on line {} to look like \"key:value\"",
-                            pair, line_number
-                        )
-                        })?;
-
-                        let (key, value) = pair.split_at(colon);
-                        let value = &value[1..];
-                        passport.fields.insert(key, value);
-                    }
-                }
-
-                Ok(passport)
+        GroupedLines::from(value)
+            .enumerate()
+            .map(|(i, group)| {
+                Passport::try_from(group).with_context(|| {
+                    format!("Unable to parse passport {}", i + 1)
+                })
             })
             .collect()
     }
@@ -339,6 +494,31 @@ impl<'input> Deref for Passport<'input> {
     }
 }
 
+impl<'input> TryFrom<Group<'input>> for Passport<'input> {
+    type Error = Error;
+
+    fn try_from(group: Group<'input>) -> Result<Self, Self::Error> {
+        let mut passport = Passport::default();
+
+        for (i, line) in group.lines().iter().enumerate() {
+            for pair in line.split_whitespace() {
+                let colon = pair.find(':').with_context(|| {
+                    format!(
+                        "Expected \"{}\" on line {} to look like \"key:value\"",
+                        pair,
+                        i + 1
+                    )
+                })?;
+
+                let (key, value) = pair.split_at(colon);
+                passport.fields.insert(key, &value[1..]);
+            }
+        }
+
+        Ok(passport)
+    }
+}
+
 impl<'input> FromIterator<(&'input str, &'input str)> for Passport<'input> {
     fn from_iter<T: IntoIterator<Item = (&'input str, &'input str)>>(
         iter: T,
@@ -349,15 +529,6 @@ impl<'input> FromIterator<(&'input str, &'input str)> for Passport<'input> {
     }
 }
 
-pub fn require_key<S, T>(
-    key: &'static str,
-) -> impl for<'a> Fn(&'a HashMap<S, T>) -> Option<&'a T>
-where
-    S: Borrow<str> + Eq + Hash,
-{
-    move |map| map.get(&key)
-}
-
 pub fn is_digit<S>(text: S) -> Option<u32>
 where
     S: AsRef<str>,
@@ -372,18 +543,12 @@ where
     move |value| predicate(min <= value && value <= max)
 }
 
-pub fn check<T>(value: T) -> Option<T> {
-    Some(value)
-}
-
 pub fn is_one_of<T, V>(values: V) -> impl Fn(T) -> Option<()>
 where
     T: PartialEq,
     V: AsRef<[T]>,
 {
-    move |value| {
-        predicate(values.as_ref().iter().any(|candidate| *candidate == value))
-    }
+    move |value| predicate(values.as_ref().contains(&value))
 }
 
 fn predicate(value: bool) -> Option<()> {
@@ -413,14 +578,19 @@ where
             return None;
         }
 
+        // Note: u32::from_str() would also accept things like a leading "+"
+        if !word.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
         word.parse().ok()
     }
 }
 
 fn validate_height(height: Height) -> Option<()> {
     let is_valid = match height {
-        Height::Centimeters(value) => 150 <= value && value <= 193,
-        Height::Inches(value) => 59 <= value && value <= 76,
+        Height::Centimeters(value) => (150..=193).contains(&value),
+        Height::Inches(value) => (59..=76).contains(&value),
     };
 
     predicate(is_valid)
@@ -430,6 +600,21 @@ fn validate_height(height: Height) -> Option<()> {
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd
+byr:1937 iyr:2017 cid:147 hgt:183cm
+
+iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884
+hcl:#cfa07d byr:1929
+
+hcl:#ae17e1 iyr:2013
+eyr:2024
+ecl:brn pid:760753108 byr:1931
+hgt:179cm
+
+hcl:#cfa07d eyr:2025 pid:166559648
+iyr:2011 ecl:brn hgt:59in
+";
+
     #[test]
     fn parse_passport() {
         let should_be: Passport = vec![
@@ -453,4 +638,180 @@ mod tests {
         assert_eq!(got.len(), 1);
         assert_eq!(got[0], should_be);
     }
+
+    #[test]
+    fn parse_the_example_batch() {
+        let got = Passports::try_from(EXAMPLE).unwrap();
+
+        let field_counts: Vec<_> =
+            got.iter().map(|passport| passport.fields.len()).collect();
+        assert_eq!(field_counts, &[8, 7, 7, 6]);
+        assert_eq!(got[2].fields["hgt"], "179cm");
+    }
+
+    #[test]
+    fn require_the_country_id() {
+        let passports = Passports::try_from(EXAMPLE).unwrap();
+        let mut policy = FieldPolicy::default_2020();
+        assert_eq!(policy.count_present(&passports), 2);
+
+        policy.required.push("cid");
+
+        assert_eq!(policy.count_present(&passports), 1);
+    }
+
+    #[test]
+    fn grouping_matches_splitting_on_blank_lines() {
+        let should_be: Passports = EXAMPLE
+            .split("\n\n")
+            .map(|record| {
+                record
+                    .split_whitespace()
+                    .map(|pair| {
+                        let mut pieces = pair.splitn(2, ':');
+                        (pieces.next().unwrap(), pieces.next().unwrap())
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let got = Passports::try_from(EXAMPLE).unwrap();
+
+        assert_eq!(got.len(), 4);
+        assert_eq!(got, should_be);
+    }
+
+    #[test]
+    fn malformed_fields_mention_the_passport_and_line() {
+        let src = "ecl:gry pid:860033327\n\niyr:2013 ecl:amb\nhcl byr:1929\n";
+
+        let err = Passports::try_from(src).unwrap_err();
+
+        assert_eq!(err.to_string(), "Unable to parse passport 2");
+        assert_eq!(
+            err.root_cause().to_string(),
+            "Expected \"hcl\" on line 2 to look like \"key:value\""
+        );
+    }
+
+    #[test]
+    fn colours_must_be_lowercase_hex() {
+        let inputs = vec![
+            ("#123abc", true),
+            ("#123abz", false),
+            ("#ABCDEF", false),
+            ("123abc", false),
+        ];
+
+        for (src, should_be_valid) in inputs {
+            let got = Colour::from_str(src);
+            assert_eq!(got.is_ok(), should_be_valid, "{}", src);
+        }
+    }
+
+    #[test]
+    fn colour_errors_name_the_failed_constraint() {
+        let inputs = vec![
+            (
+                "#123",
+                "Hex colours must be exactly 6 digits, found 3 in \"#123\"",
+            ),
+            (
+                "123abc",
+                "Hex colours must start with \"#\", found \"123abc\"",
+            ),
+            (
+                "#12g",
+                "Hex colours may only contain 0-9 and a-f, found \"g\"",
+            ),
+        ];
+
+        for (src, should_be) in inputs {
+            let err = Colour::from_str(src).err().unwrap();
+            assert_eq!(err.to_string(), should_be);
+        }
+    }
+
+    #[test]
+    fn height_errors_name_the_failed_constraint() {
+        let err = Height::from_str("150").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Heights must end in \"cm\" or \"in\" (e.g. \"150cm\" or \"90in\"), found \"150\""
+        );
+
+        let err = Height::from_str("1.5in").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The height must be a whole number, found \"1.5in\""
+        );
+    }
+
+    #[test]
+    fn heights_round_trip() {
+        let inputs = vec![
+            ("150cm", Height::Centimeters(150), true),
+            ("59in", Height::Inches(59), false),
+        ];
+
+        for (src, should_be, is_metric) in inputs {
+            let got = Height::from_str(src).unwrap();
+
+            assert_eq!(got, should_be);
+            assert_eq!(got.is_metric(), is_metric);
+            assert_eq!(
+                got.value(),
+                src[..src.len() - 2].parse::<u32>().unwrap()
+            );
+            assert_eq!(got.to_string(), src);
+        }
+    }
+
+    #[test]
+    fn passport_ids_must_be_nine_digits() {
+        let inputs = vec![
+            ("000000001", Some(1)),
+            ("0123456789", None),
+            ("00000000a", None),
+            ("+00000001", None),
+            (" 00000001", None),
+        ];
+
+        for (src, should_be) in inputs {
+            let got = decimal_number_with_length(9)(src);
+            assert_eq!(got, should_be, "{}", src);
+        }
+    }
+
+    #[test]
+    fn explain_why_passports_are_invalid() {
+        let raw = r#"
+            eyr:1972 cid:100
+            hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926
+
+            iyr:2019
+            hcl:#602927 eyr:1967 hgt:170cm
+            ecl:grn pid:012533040 byr:1946
+
+            hcl:dab227 iyr:2012
+            ecl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277
+
+            hgt:59cm ecl:zzz
+            eyr:2038 hcl:74454a iyr:2023
+            pid:3556412378 byr:2007
+
+            iyr:2010 hgt:158cm hcl:#b6652a ecl:blu eyr:2021 pid:093154719"#;
+        let should_be = vec![
+            Err(ValidationError::OutOfRange("eyr")),
+            Err(ValidationError::OutOfRange("eyr")),
+            Err(ValidationError::WrongFormat("hcl")),
+            Err(ValidationError::OutOfRange("byr")),
+            Err(ValidationError::Missing("byr")),
+        ];
+
+        let passports = Passports::try_from(raw).unwrap();
+        let got: Vec<_> = passports.iter().map(validate).collect();
+
+        assert_eq!(got, should_be);
+    }
 }
@@ -1,6 +1,10 @@
 use anyhow::Error;
 use aoc_core::Lines;
-use std::{ops::Range, str::FromStr};
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
 
 /// Day 5a: Binary Boarding (part 1)
 ///
@@ -68,12 +72,28 @@ use std::{ops::Range, str::FromStr};
 ///
 /// As a sanity check, look through your list of boarding passes. What is the
 /// highest seat ID on a boarding pass?
-#[aoc_macros::challenge]
+///
+/// # Examples
+///
+/// ```example
+/// input:
+/// FBFBBFFRLR
+/// BFFFBBFRRR
+/// FFFBBBFRRR
+/// BBFFBBFRLL
+/// expected:
+/// 820
+/// ```
+#[aoc_macros::challenge(
+    tags("binary"),
+    line_oriented,
+    detect = "looks_like_input"
+)]
 pub fn part_1(boarding_passes: Lines<BoardingPass>) -> Result<u32, Error> {
     boarding_passes
         .iter()
-        .map(|b| b.location().id())
         .max()
+        .map(|b| b.location().id())
         .ok_or_else(|| Error::msg("No boarding passes provided"))
 }
 
@@ -92,24 +112,84 @@ pub fn part_1(boarding_passes: Lines<BoardingPass>) -> Result<u32, Error> {
 /// -1 from yours will be in your list.
 ///
 /// What is the ID of your seat?
-#[aoc_macros::challenge]
+#[aoc_macros::challenge(
+    tags("binary"),
+    line_oriented,
+    detect = "looks_like_input"
+)]
 pub fn part_2(boarding_passes: Lines<BoardingPass>) -> Result<u32, Error> {
-    let mut seat_ids: Vec<_> =
+    let seat_ids: Vec<_> =
         boarding_passes.iter().map(|b| b.location().id()).collect();
-    seat_ids.sort();
 
-    for window in seat_ids.windows(2) {
-        let first = window[0];
-        let second = window[1];
+    match missing_seats(&seat_ids).as_slice() {
+        [seat] => Ok(*seat),
+        [] => Err(Error::msg("Unable to find the seat number")),
+        candidates => anyhow::bail!(
+            "Expected exactly one empty seat, but found {}: {:?}",
+            candidates.len(),
+            candidates
+        ),
+    }
+}
+
+/// Boarding passes are strings of `F`, `B`, `L`, and `R`, one per line.
+fn looks_like_input(input: &str) -> bool {
+    matches!(
+        input.parse::<Lines<BoardingPass>>(),
+        Ok(passes) if !passes.is_empty()
+    )
+}
+
+/// Find every seat ID that is missing from `ids` while both of its neighbours
+/// are present.
+pub fn missing_seats(ids: &[u32]) -> Vec<u32> {
+    let mut ids = ids.to_vec();
+    ids.sort_unstable();
+    ids.dedup();
+
+    ids.windows(2)
+        .filter(|window| window[0] + 2 == window[1])
+        .map(|window| window[0] + 1)
+        .collect()
+}
+
+/// Draw the plane's 128 rows of 8 seats, where `#` is a seat with a boarding
+/// pass, `?` is an empty seat with occupied seats on either side (i.e. your
+/// seat), and a space is a seat that is empty or doesn't exist.
+pub fn render_seat_map(passes: &[BoardingPass]) -> String {
+    const ROWS: u32 = 128;
+    const COLUMNS: u32 = 8;
+
+    let mut occupied = vec![false; (ROWS * COLUMNS) as usize];
+
+    for pass in passes {
+        occupied[pass.location().id() as usize] = true;
+    }
 
-        let candidate = first + 1;
+    let is_occupied =
+        |id: u32| occupied.get(id as usize).copied().unwrap_or(false);
 
-        if candidate != second {
-            return Ok(candidate);
+    let mut map = String::new();
+
+    for row in 0..ROWS {
+        for column in 0..COLUMNS {
+            let id = Seat::new(row, column).id();
+
+            let symbol = if is_occupied(id) {
+                '#'
+            } else if id > 0 && is_occupied(id - 1) && is_occupied(id + 1) {
+                '?'
+            } else {
+                ' '
+            };
+
+            map.push(symbol);
         }
+
+        map.push('\n');
     }
 
-    Err(Error::msg("Unable to find the seat number"))
+    map
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -119,15 +199,44 @@ pub struct BoardingPass {
 }
 
 impl BoardingPass {
+    /// Find the seat this boarding pass is for.
+    ///
+    /// Each step of the binary space partitioning picks the upper or lower
+    /// half, so the directions are really just the bits of the row and
+    /// column numbers (`Up` is 1 and `Down` is 0).
     pub fn location(&self) -> Seat {
-        let row = partition_range(&self.rows, 0..128);
-        let seat = partition_range(&self.seats, 0..8);
+        Seat::new(decode(&self.rows), decode(&self.seats))
+    }
+}
 
-        Seat::new(row, seat)
+/// Boarding passes are ordered by the [`Seat`] they are for.
+impl Ord for BoardingPass {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.location().cmp(&other.location())
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+impl PartialOrd for BoardingPass {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Eq for BoardingPass {}
+
+/// Boarding passes are serialized as the [`Seat`] they are for.
+#[cfg(feature = "serde")]
+impl serde::Serialize for BoardingPass {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.location().serialize(serializer)
+    }
+}
+
+/// A seat on the plane, ordered from front to back (i.e. by [`Seat::id()`]).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Seat {
     pub row: u32,
     pub column: u32,
@@ -138,11 +247,33 @@ impl Seat {
         Seat { row, column }
     }
 
+    /// The inverse of [`Seat::id()`].
+    pub const fn from_id(id: u32) -> Self {
+        Seat::new(id / 8, id % 8)
+    }
+
     pub const fn id(self) -> u32 {
         self.column + self.row * 8
     }
 }
 
+/// Seats are serialized as `{"row": 70, "column": 7, "id": 567}`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Seat {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut seat = serializer.serialize_struct("Seat", 3)?;
+        seat.serialize_field("row", &self.row)?;
+        seat.serialize_field("column", &self.column)?;
+        seat.serialize_field("id", &self.id())?;
+        seat.end()
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Direction {
     Up,
@@ -153,56 +284,126 @@ impl FromStr for BoardingPass {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        anyhow::ensure!(s.len() == 7 + 3);
-
-        let (rows, seats) = s.split_at(7);
-
-        let rows = rows
-            .chars()
-            .map(|c| match c {
-                'F' => Ok(Direction::Down),
-                'B' => Ok(Direction::Up),
-                other => anyhow::bail!(
-                    "Expected \"F\" or \"B\", found \"{}\"",
-                    other
-                ),
-            })
-            .collect::<Result<Vec<_>, _>>()?;
-        let seats = seats
-            .chars()
-            .map(|c| match c {
-                'L' => Ok(Direction::Down),
-                'R' => Ok(Direction::Up),
-                other => anyhow::bail!(
-                    "Expected \"L\" or \"R\", found \"{}\"",
-                    other
-                ),
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+        const ROW_CHARACTERS: usize = 7;
+        const SEAT_CHARACTERS: usize = 3;
+
+        let length = s.chars().count();
+        anyhow::ensure!(
+            length == ROW_CHARACTERS + SEAT_CHARACTERS,
+            "Expected {} row characters followed by {} seat characters, but \"{}\" is {} characters long",
+            ROW_CHARACTERS,
+            SEAT_CHARACTERS,
+            s,
+            length
+        );
+
+        let mut rows = Vec::with_capacity(ROW_CHARACTERS);
+        let mut seats = Vec::with_capacity(SEAT_CHARACTERS);
+
+        for (index, c) in s.chars().enumerate() {
+            if index < ROW_CHARACTERS {
+                rows.push(match c {
+                    'F' => Direction::Down,
+                    'B' => Direction::Up,
+                    other => anyhow::bail!(
+                        "Expected \"F\" or \"B\" at index {}, found \"{}\"",
+                        index,
+                        other
+                    ),
+                });
+            } else {
+                seats.push(match c {
+                    'L' => Direction::Down,
+                    'R' => Direction::Up,
+                    other => anyhow::bail!(
+                        "Expected \"L\" or \"R\" at index {}, found \"{}\"",
+                        index,
+                        other
+                    ),
+                });
+            }
+        }
 
         Ok(BoardingPass { rows, seats })
     }
 }
 
-fn partition_range(commands: &[Direction], range: Range<u32>) -> u32 {
-    let Range { start, end } = range;
-    let midpoint = (start + end) / 2;
-
-    if commands.is_empty() {
-        return midpoint;
-    }
+impl Display for BoardingPass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for direction in &self.rows {
+            match direction {
+                Direction::Down => write!(f, "F")?,
+                Direction::Up => write!(f, "B")?,
+            }
+        }
 
-    let (direction, rest) = commands.split_first().expect("already checked");
+        for direction in &self.seats {
+            match direction {
+                Direction::Down => write!(f, "L")?,
+                Direction::Up => write!(f, "R")?,
+            }
+        }
 
-    match direction {
-        Direction::Up => partition_range(rest, midpoint..end),
-        Direction::Down => partition_range(rest, start..midpoint),
+        Ok(())
     }
 }
 
+fn decode(directions: &[Direction]) -> u32 {
+    directions
+        .iter()
+        .fold(0, |number, direction| match direction {
+            Direction::Up => number * 2 + 1,
+            Direction::Down => number * 2,
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::ops::Range;
+
+    /// The original implementation, which repeatedly halves the range.
+    fn partition_range(commands: &[Direction], range: Range<u32>) -> u32 {
+        let Range { start, end } = range;
+        let midpoint = (start + end) / 2;
+
+        if commands.is_empty() {
+            return midpoint;
+        }
+
+        let (direction, rest) =
+            commands.split_first().expect("already checked");
+
+        match direction {
+            Direction::Up => partition_range(rest, midpoint..end),
+            Direction::Down => partition_range(rest, start..midpoint),
+        }
+    }
+
+    #[test]
+    fn decoding_agrees_with_partitioning_for_every_seat() {
+        for id in 0..1024 {
+            let raw: String = format!("{:010b}", id)
+                .chars()
+                .enumerate()
+                .map(|(i, bit)| match (i < 7, bit) {
+                    (true, '1') => 'B',
+                    (true, _) => 'F',
+                    (false, '1') => 'R',
+                    (false, _) => 'L',
+                })
+                .collect();
+            let pass: BoardingPass = raw.parse().unwrap();
+
+            let partitioned = Seat::new(
+                partition_range(&pass.rows, 0..128),
+                partition_range(&pass.seats, 0..8),
+            );
+
+            assert_eq!(pass.location(), partitioned, "{}", raw);
+            assert_eq!(pass.location(), Seat::from_id(id), "{}", raw);
+        }
+    }
 
     #[test]
     fn correct_seat_id_calcs() {
@@ -218,6 +419,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn seat_ids_round_trip() {
+        let seats = vec![Seat::new(70, 7), Seat::new(14, 7), Seat::new(102, 4)];
+
+        for seat in seats {
+            assert_eq!(Seat::from_id(seat.id()), seat);
+        }
+    }
+
     #[test]
     fn find_location_for_known_boarding_passes() {
         let inputs = vec![
@@ -233,4 +443,151 @@ mod tests {
             assert_eq!(got, should_be);
         }
     }
+
+    #[test]
+    fn seats_are_sorted_by_id() {
+        let mut seats: Vec<_> = [820, 7, 119, 8, 567, 0, 1023, 357, 15, 16]
+            .iter()
+            .map(|&id| Seat::from_id(id))
+            .collect();
+
+        seats.sort();
+
+        let ids: Vec<_> = seats.iter().map(|s| s.id()).collect();
+        assert_eq!(ids, &[0, 7, 8, 15, 16, 119, 357, 567, 820, 1023]);
+    }
+
+    #[test]
+    fn boarding_passes_are_sorted_by_seat() {
+        let mut passes: Vec<BoardingPass> =
+            ["BBFFBBFRLL", "FBFBBFFRLR", "FFFBBBFRRR", "BFFFBBFRRR"]
+                .iter()
+                .map(|raw| raw.parse().unwrap())
+                .collect();
+
+        passes.sort();
+
+        let ids: Vec<_> = passes.iter().map(|p| p.location().id()).collect();
+        assert_eq!(ids, &[119, 357, 567, 820]);
+    }
+
+    #[test]
+    fn boarding_passes_round_trip() {
+        for raw in &["FBFBBFFRLR", "BFFFBBFRRR", "FFFBBBFRRR", "BBFFBBFRLL"] {
+            let boarding_pass: BoardingPass = raw.parse().unwrap();
+
+            assert_eq!(boarding_pass.to_string(), *raw);
+        }
+    }
+
+    #[test]
+    fn no_missing_seats() {
+        let got = missing_seats(&[3, 1, 2, 4]);
+
+        assert!(got.is_empty());
+    }
+
+    #[test]
+    fn a_single_missing_seat() {
+        let got = missing_seats(&[10, 8, 7, 11]);
+
+        assert_eq!(got, vec![9]);
+    }
+
+    #[test]
+    fn multiple_missing_seats() {
+        let ids = [1, 2, 4, 5, 7, 20];
+
+        let got = missing_seats(&ids);
+
+        assert_eq!(got, vec![3, 6]);
+    }
+
+    #[test]
+    fn bad_row_characters_are_reported_with_their_index() {
+        let err = "FBFXBBFRLR".parse::<BoardingPass>().unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Expected \"F\" or \"B\" at index 3, found \"X\""
+        );
+    }
+
+    #[test]
+    fn bad_seat_characters_are_reported_with_their_index() {
+        let err = "FBFBBFFRLF".parse::<BoardingPass>().unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Expected \"L\" or \"R\" at index 9, found \"F\""
+        );
+    }
+
+    #[test]
+    fn boarding_passes_must_have_ten_characters() {
+        assert!("FBFBBFFRL".parse::<BoardingPass>().is_err());
+        assert!("FBFBBFFRLRR".parse::<BoardingPass>().is_err());
+        assert!("FBFBBF\u{e9}RLR".parse::<BoardingPass>().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serialize_boarding_passes_as_json() {
+        let pass: BoardingPass = "BFFFBBFRRR".parse().unwrap();
+
+        let got = serde_json::to_value(&pass).unwrap();
+
+        assert_eq!(
+            got,
+            serde_json::json!({ "row": 70, "column": 7, "id": 567 })
+        );
+        assert_eq!(serde_json::to_value(pass.location()).unwrap(), got);
+    }
+
+    #[test]
+    fn part_2_rejects_ambiguous_inputs() {
+        let passes: Lines<BoardingPass> =
+            "FFFFFFFLLL\nFFFFFFFLRL\nFFFFFFFRLL\nFFFFFFFRRL"
+                .parse()
+                .unwrap();
+
+        let err = part_2(passes).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Expected exactly one empty seat, but found 3: [1, 3, 5]"
+        );
+    }
+
+    #[test]
+    fn render_the_example_boarding_passes() {
+        let passes: Vec<BoardingPass> =
+            ["BFFFBBFRRR", "FFFBBBFRRR", "BBFFBBFRLL"]
+                .iter()
+                .map(|p| p.parse().unwrap())
+                .collect();
+
+        let got = render_seat_map(&passes);
+
+        let rows: Vec<_> = got.lines().collect();
+        assert_eq!(rows.len(), 128);
+        assert!(rows.iter().all(|row| row.len() == 8));
+        assert_eq!(rows[70], "       #");
+        assert_eq!(rows[14], "       #");
+        assert_eq!(rows[102], "    #   ");
+        assert_eq!(got.matches('#').count(), 3);
+        assert!(!got.contains('?'));
+    }
+
+    #[test]
+    fn missing_seats_between_passengers_are_highlighted() {
+        let passes: Vec<BoardingPass> = ["FFFFFFFLLL", "FFFFFFFLRL"]
+            .iter()
+            .map(|p| p.parse().unwrap())
+            .collect();
+
+        let got = render_seat_map(&passes);
+
+        assert_eq!(got.lines().next().unwrap(), "#?#     ");
+    }
 }
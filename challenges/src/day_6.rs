@@ -3,7 +3,7 @@ use aoc_core::GroupedLines;
 use bitvec::{slice::BitSlice, vec::BitVec};
 use std::{convert::TryFrom, str::FromStr};
 
-/// Day 6a: Custom Customs (part a)
+/// Day 6a: Custom Customs (part 1)
 ///
 /// # Description
 ///
@@ -66,17 +66,35 @@ use std::{convert::TryFrom, str::FromStr};
 ///
 /// For each group, count the number of questions to which anyone answered "yes".
 /// What is the sum of those counts?
-#[aoc_macros::challenge]
+///
+/// # Examples
+///
+/// ```example
+/// input:
+/// abc
+///
+/// a
+/// b
+/// c
+///
+/// ab
+/// ac
+///
+/// a
+/// a
+/// a
+/// a
+///
+/// b
+/// expected:
+/// 11
+/// ```
+#[aoc_macros::challenge(tags("sets"), detect = "looks_like_input")]
 pub fn part_1(responses: Responses) -> Result<usize, Error> {
-    Ok(responses
-        .0
-        .iter()
-        .map(|group| group.merge_any())
-        .map(|merged_answer| merged_answer.pop_count())
-        .sum())
+    Ok(responses.total_any())
 }
 
-/// Day 6b: ass
+/// Day 6b: Custom Customs (part 2)
 ///
 /// # Description
 ///
@@ -120,35 +138,90 @@ pub fn part_1(responses: Responses) -> Result<usize, Error> {
 ///
 /// For each group, count the number of questions to which everyone answered
 /// "yes". What is the sum of those counts?
-#[aoc_macros::challenge]
+///
+/// # Examples
+///
+/// ```example
+/// input:
+/// abc
+///
+/// a
+/// b
+/// c
+///
+/// ab
+/// ac
+///
+/// a
+/// a
+/// a
+/// a
+///
+/// b
+/// expected:
+/// 6
+/// ```
+#[aoc_macros::challenge(tags("sets"), detect = "looks_like_input")]
 pub fn part_2(responses: Responses) -> Result<usize, Error> {
-    Ok(responses
-        .0
-        .iter()
-        .map(|group| group.merge_all())
-        .map(|merged_answer| merged_answer.pop_count())
-        .sum())
+    Ok(responses.total_all())
+}
+
+/// Each person's answers are a line of lowercase letters.
+fn looks_like_input(input: &str) -> bool {
+    let mut answers = input.split_whitespace().peekable();
+
+    answers.peek().is_some()
+        && answers.all(|a| a.bytes().all(|b| b.is_ascii_lowercase()))
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Responses(Vec<ResponseGroup>);
 
+impl Responses {
+    /// The sum of each group's questions that anyone answered "yes" to.
+    pub fn total_any(&self) -> usize {
+        self.0.iter().map(|group| group.union().pop_count()).sum()
+    }
+
+    /// The sum of each group's questions that everyone answered "yes" to.
+    pub fn total_all(&self) -> usize {
+        self.0
+            .iter()
+            .map(|group| group.intersection().pop_count())
+            .sum()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ResponseGroup(Vec<Response>);
 
 impl ResponseGroup {
-    pub fn merge_any(&self) -> Response {
+    /// The questions anyone in the group answered "yes" to.
+    pub fn union(&self) -> Response {
         self.merge_with(Response::default(), |acc, elem| {
             *acc |= elem.iter().copied();
         })
     }
 
-    pub fn merge_all(&self) -> Response {
-        self.merge_with(Response(BitVec::repeat(true, 26)), |acc, elem| {
+    /// The questions everyone in the group answered "yes" to.
+    pub fn intersection(&self) -> Response {
+        let width = self.0.iter().map(Response::width).max().unwrap_or(0);
+
+        self.merge_with(Response(BitVec::repeat(true, width)), |acc, elem| {
             *acc &= elem.iter().copied();
         })
     }
 
+    /// An alias for [`ResponseGroup::union()`].
+    pub fn merge_any(&self) -> Response {
+        self.union()
+    }
+
+    /// An alias for [`ResponseGroup::intersection()`].
+    pub fn merge_all(&self) -> Response {
+        self.intersection()
+    }
+
     fn merge_with<F>(&self, init: Response, mut merger: F) -> Response
     where
         F: FnMut(&mut BitVec, &BitSlice),
@@ -169,21 +242,29 @@ impl<'input> TryFrom<&'input str> for Responses {
     type Error = Error;
 
     fn try_from(value: &'input str) -> Result<Self, Self::Error> {
-        let groups = GroupedLines::try_from(value)?;
+        let groups = GroupedLines::from(value);
         Responses::try_from(groups)
     }
 }
 
+impl FromStr for Responses {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Responses::try_from(s)
+    }
+}
+
 impl<'input> TryFrom<GroupedLines<'input>> for Responses {
     type Error = Error;
 
     fn try_from(value: GroupedLines<'input>) -> Result<Self, Self::Error> {
-        let mut response_groups = Vec::new();
+        let mut response_groups = Vec::with_capacity(value.clone().count());
 
-        for lines in value {
-            let mut group = Vec::with_capacity(lines.len());
+        for raw_group in value {
+            let mut group = Vec::with_capacity(raw_group.lines().len());
 
-            for line in lines {
+            for line in raw_group {
                 let response: Response = line.parse()?;
                 group.push(response);
             }
@@ -195,29 +276,73 @@ impl<'input> TryFrom<GroupedLines<'input>> for Responses {
     }
 }
 
+/// The number of questions on a customs declaration form, "a" to "z".
+pub const DEFAULT_ALPHABET_SIZE: usize = 26;
+
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct Response(BitVec);
 
 impl Response {
+    /// Parse a response to a form which only has the first `alphabet_size`
+    /// questions (e.g. `3` means only "a", "b", and "c" are valid).
+    pub fn with_alphabet(
+        value: &str,
+        alphabet_size: usize,
+    ) -> Result<Self, Error> {
+        anyhow::ensure!(
+            (1..=DEFAULT_ALPHABET_SIZE).contains(&alphabet_size),
+            "The alphabet must have between 1 and {} letters, not {}",
+            DEFAULT_ALPHABET_SIZE,
+            alphabet_size
+        );
+
+        let last_letter = (b'a' + alphabet_size as u8 - 1) as char;
+        let mut answers = BitVec::repeat(false, alphabet_size);
+
+        for letter in value.chars() {
+            match letter {
+                'a'..='z' if letter <= last_letter => {
+                    answers.set(letter as usize - 'a' as usize, true)
+                },
+                // stray whitespace (e.g. the "\r" from CRLF line endings)
+                // isn't an answer
+                other if other.is_whitespace() => {},
+                other => anyhow::bail!(
+                    "Answers should be a letter from \"a\" to \"{}\", found \"{}\"",
+                    last_letter,
+                    other
+                ),
+            }
+        }
+
+        Ok(Response(answers))
+    }
+
     pub fn pop_count(&self) -> usize {
         self.0.iter().filter(|value| **value).count()
     }
+
+    /// The questions that were answered "yes" to, as letters.
+    pub fn as_set(&self) -> Vec<char> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(_, answered)| **answered)
+            .map(|(index, _)| (b'a' + index as u8) as char)
+            .collect()
+    }
+
+    /// The number of questions on the form this response was for.
+    pub fn width(&self) -> usize {
+        self.0.len()
+    }
 }
 
 impl<'input> TryFrom<&'input str> for Response {
     type Error = Error;
 
     fn try_from(value: &'input str) -> Result<Self, Self::Error> {
-        let mut answers = BitVec::repeat(false, 26);
-
-        for letter in value.chars() {
-            match letter {
-                'a'..='z' => answers.set(letter as usize - 'a' as usize, true),
-                _ => todo!(),
-            }
-        }
-
-        Ok(Response(answers))
+        Response::with_alphabet(value, DEFAULT_ALPHABET_SIZE)
     }
 }
 
@@ -233,6 +358,8 @@ impl FromStr for Response {
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "abc\n\na\nb\nc\n\nab\nac\n\na\na\na\na\n\nb";
+
     #[test]
     fn parse_responses() {
         let inputs = vec![
@@ -250,4 +377,101 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn parse_the_example_groups() {
+        let got: Responses = EXAMPLE.parse().unwrap();
+
+        let group_sizes: Vec<_> = got.0.iter().map(|g| g.0.len()).collect();
+        assert_eq!(group_sizes, &[1, 3, 2, 4, 1]);
+        assert_eq!(got, Responses::try_from(EXAMPLE).unwrap());
+    }
+
+    #[test]
+    fn totals_for_the_example_groups() {
+        let got: Responses = EXAMPLE.parse().unwrap();
+
+        assert_eq!(got.total_any(), 11);
+        assert_eq!(got.total_all(), 6);
+    }
+
+    #[test]
+    fn union_and_intersection_of_the_example_groups() {
+        let responses: Responses = EXAMPLE.parse().unwrap();
+
+        let unions: Vec<_> =
+            responses.0.iter().map(|g| g.union().as_set()).collect();
+        let intersections: Vec<_> = responses
+            .0
+            .iter()
+            .map(|g| g.intersection().as_set())
+            .collect();
+
+        assert_eq!(
+            unions,
+            vec![
+                vec!['a', 'b', 'c'],
+                vec!['a', 'b', 'c'],
+                vec!['a', 'b', 'c'],
+                vec!['a'],
+                vec!['b'],
+            ]
+        );
+        assert_eq!(
+            intersections,
+            vec![vec!['a', 'b', 'c'], vec![], vec!['a'], vec!['a'], vec!['b']]
+        );
+        for group in &responses.0 {
+            assert_eq!(group.merge_any(), group.union());
+            assert_eq!(group.merge_all(), group.intersection());
+        }
+    }
+
+    #[test]
+    fn ignore_trailing_carriage_return() {
+        let got: Response = "abc\r".parse().unwrap();
+        let should_be: Response = "abc".parse().unwrap();
+
+        assert_eq!(got, should_be);
+    }
+
+    #[test]
+    fn unexpected_characters_are_an_error() {
+        assert!(Response::from_str("ab1").is_err());
+        assert!(Response::from_str("aBc").is_err());
+    }
+
+    #[test]
+    fn the_default_alphabet_is_a_to_z() {
+        let got = Response::from_str("az").unwrap();
+
+        assert_eq!(got.width(), 26);
+        assert_eq!(got.pop_count(), 2);
+        assert_eq!(got, Response::with_alphabet("az", 26).unwrap());
+    }
+
+    #[test]
+    fn use_a_smaller_alphabet() {
+        let got = Response::with_alphabet("ca", 3).unwrap();
+        assert_eq!(got.width(), 3);
+        assert_eq!(got.pop_count(), 2);
+
+        let err = Response::with_alphabet("abd", 3).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Answers should be a letter from \"a\" to \"c\", found \"d\""
+        );
+        assert!(Response::with_alphabet("a", 0).is_err());
+        assert!(Response::with_alphabet("a", 27).is_err());
+
+        let group = ResponseGroup(vec![
+            Response::with_alphabet("abc", 3).unwrap(),
+            Response::with_alphabet("bc", 3).unwrap(),
+        ]);
+        assert_eq!(
+            group.merge_all(),
+            Response::with_alphabet("bc", 3).unwrap()
+        );
+        assert_eq!(group.merge_any().pop_count(), 3);
+    }
 }
@@ -5,4 +5,157 @@ pub mod day_4;
 pub mod day_5;
 pub mod day_6;
 
-pub use aoc_core::all_challenges;
+pub use aoc_core::{
+    all_challenges, all_challenges_sorted, parse_number,
+    validate_unique_challenges, Challenge,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{collections::HashSet, fs, path::Path};
+
+    /// Get the `(module_name, source_code)` for every `day_N.rs` file in the
+    /// `src/` directory.
+    fn day_modules() -> Vec<(String, String)> {
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut modules = Vec::new();
+
+        for entry in fs::read_dir(&src).unwrap() {
+            let path = entry.unwrap().path();
+            let name = match path.file_stem().and_then(|s| s.to_str()) {
+                Some(name) if name.starts_with("day_") => name.to_string(),
+                _ => continue,
+            };
+
+            let source = fs::read_to_string(&path).unwrap();
+            modules.push((name, source));
+        }
+
+        modules
+    }
+
+    #[test]
+    fn every_day_is_declared() {
+        let lib_rs = include_str!("lib.rs");
+
+        for (name, _) in day_modules() {
+            let declaration = format!("pub mod {};", name);
+            assert!(
+                lib_rs.lines().any(|line| line.trim() == declaration),
+                "\"{}.rs\" exists but isn't declared with \"{}\"",
+                name,
+                declaration
+            );
+        }
+    }
+
+    #[test]
+    fn all_challenges_are_registered() {
+        let annotated_functions: usize = day_modules()
+            .iter()
+            .map(|(_, source)| {
+                source.matches("#[aoc_macros::challenge").count()
+            })
+            .sum();
+
+        assert_eq!(all_challenges().count(), annotated_functions);
+    }
+
+    #[test]
+    fn every_challenge_header_is_valid() {
+        let headers: Vec<_> = day_modules()
+            .into_iter()
+            .flat_map(|(name, source)| {
+                source
+                    .lines()
+                    .filter(|line| line.starts_with("/// Day "))
+                    .map(|line| (name.clone(), line.to_string()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        for (module, header) in &headers {
+            let (day, name, part) = aoc_core::parse_challenge_header(header)
+                .unwrap_or_else(|e| panic!("{}: {}", module, e));
+
+            assert_eq!(format!("day_{}", day), *module);
+            let number = match part {
+                Some(part) => format!("{}{}", day, part),
+                None => day,
+            };
+            let challenge =
+                all_challenges().find(|c| c.number == number).unwrap();
+            assert_eq!(challenge.name, name);
+        }
+
+        assert_eq!(headers.len(), all_challenges().count());
+    }
+
+    #[test]
+    fn challenge_numbers_are_unique() {
+        let mut seen = HashSet::new();
+
+        for &(year, number) in aoc_core::CHALLENGE_NUMBERS.iter() {
+            assert!(
+                seen.insert((year, number)),
+                "{}/{} was registered more than once",
+                year,
+                number
+            );
+        }
+
+        assert_eq!(seen.len(), all_challenges().count());
+    }
+
+    #[test]
+    fn every_example_is_solved() {
+        let mut failures = Vec::new();
+        let mut checked = 0;
+
+        for challenge in all_challenges_sorted() {
+            for (i, example) in challenge.examples.iter().enumerate() {
+                checked += 1;
+
+                match (challenge.solve)(example.input) {
+                    Ok(got) if got == example.expected => {},
+                    Ok(got) => failures.push(format!(
+                        "{} example {}: expected {:?} but got {:?}",
+                        challenge.number,
+                        i + 1,
+                        example.expected,
+                        got
+                    )),
+                    Err(e) => failures.push(format!(
+                        "{} example {}: {:?}",
+                        challenge.number,
+                        i + 1,
+                        e
+                    )),
+                }
+            }
+        }
+
+        assert!(checked > 0, "No examples were found");
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn both_parts_of_a_day_are_distinct() {
+        let part_a: Vec<_> =
+            all_challenges().filter(|c| c.number == "4a").collect();
+        let part_b: Vec<_> =
+            all_challenges().filter(|c| c.number == "4b").collect();
+
+        assert_eq!(part_a.len(), 1);
+        assert_eq!(part_b.len(), 1);
+        // comparing function pointers isn't reliable because the compiler may
+        // merge or duplicate functions, so compare the registered metadata
+        assert_ne!(
+            (part_a[0].number, part_a[0].name),
+            (part_b[0].number, part_b[0].name)
+        );
+        assert_eq!(part_a[0].name, "Passport Processing");
+        assert_eq!(part_b[0].name, "Passport Processing (part 2)");
+    }
+}
@@ -0,0 +1,255 @@
+use anyhow::{Context, Error};
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// The environment variable containing the user's adventofcode.com session
+/// token.
+pub const SESSION_VARIABLE: &str = "AOC_SESSION";
+
+/// How many times to send a request before giving up on being rate-limited.
+const MAX_ATTEMPTS: u32 = 4;
+/// How long to wait after the first rate-limited request, doubling with each
+/// subsequent attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+/// The minimum time between two requests to adventofcode.com.
+const MIN_REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Fetch the puzzle input for a particular day.
+pub fn download(day: u32, session: &str) -> Result<String, Error> {
+    let url = format!("https://adventofcode.com/2020/day/{}/input", day);
+
+    let send = || {
+        wait_for_rate_limit();
+        ureq::get(&url)
+            .set("Cookie", &format!("session={}", session))
+            .call()
+            .map_err(Box::new)
+    };
+    let result =
+        call_with_retries(send, |e| is_rate_limited(e), std::thread::sleep);
+
+    match result.map_err(|e| *e) {
+        Ok(response) => response
+            .into_string()
+            .context("Unable to read the response body"),
+        Err(ureq::Error::Status(status, _)) => Err(status_error(day, status)),
+        Err(e) => Err(Error::new(e))
+            .with_context(|| format!("Unable to send a request to {}", url)),
+    }
+}
+
+/// Get the input for a particular day from the cache directory, only
+/// calling `fetch` (and saving the result) when it hasn't been downloaded yet
+/// or `force` is set.
+pub fn cached<F>(
+    cache_dir: &Path,
+    day: u32,
+    force: bool,
+    fetch: F,
+) -> Result<String, Error>
+where
+    F: FnOnce() -> Result<String, Error>,
+{
+    let filename = cache_path(cache_dir, day);
+
+    if !force && filename.is_file() {
+        return std::fs::read_to_string(&filename).with_context(|| {
+            format!("Unable to read \"{}\"", filename.display())
+        });
+    }
+
+    let input = fetch()?;
+
+    std::fs::create_dir_all(cache_dir).with_context(|| {
+        format!("Unable to create \"{}\"", cache_dir.display())
+    })?;
+    std::fs::write(&filename, &input).with_context(|| {
+        format!("Unable to save to \"{}\"", filename.display())
+    })?;
+
+    Ok(input)
+}
+
+fn cache_path(cache_dir: &Path, day: u32) -> PathBuf {
+    cache_dir.join(format!("day_{}.txt", day))
+}
+
+/// Make sure requests are at least [`MIN_REQUEST_INTERVAL`] apart.
+fn wait_for_rate_limit() {
+    static LAST_REQUEST: Mutex<Option<Instant>> = Mutex::new(None);
+
+    let mut last_request =
+        LAST_REQUEST.lock().unwrap_or_else(|e| e.into_inner());
+
+    if let Some(last) = *last_request {
+        let elapsed = last.elapsed();
+        if elapsed < MIN_REQUEST_INTERVAL {
+            std::thread::sleep(MIN_REQUEST_INTERVAL - elapsed);
+        }
+    }
+
+    *last_request = Some(Instant::now());
+}
+
+fn is_rate_limited(e: &ureq::Error) -> bool {
+    matches!(e, ureq::Error::Status(429, _))
+}
+
+/// Keep sending a request while the server says we are being rate-limited
+/// (HTTP 429), backing off exponentially between attempts.
+fn call_with_retries<T, E, F, R, S>(
+    mut send: F,
+    is_rate_limited: R,
+    mut sleep: S,
+) -> Result<T, E>
+where
+    F: FnMut() -> Result<T, E>,
+    R: Fn(&E) -> bool,
+    S: FnMut(Duration),
+{
+    let mut delay = INITIAL_BACKOFF;
+
+    for _ in 1..MAX_ATTEMPTS {
+        match send() {
+            Err(e) if is_rate_limited(&e) => {
+                sleep(delay);
+                delay *= 2;
+            },
+            other => return other,
+        }
+    }
+
+    send()
+}
+
+/// Turn an unsuccessful status code into a human-friendly error.
+fn status_error(day: u32, status: u16) -> Error {
+    match status {
+        400 => anyhow::anyhow!(
+            "The server rejected the session token. Make sure {} is set to \
+             the \"session\" cookie from adventofcode.com",
+            SESSION_VARIABLE
+        ),
+        404 => anyhow::anyhow!("The input for day {} isn't available yet", day),
+        429 => anyhow::anyhow!(
+            "The server is still rate-limiting requests after {} attempts, \
+             try again later",
+            MAX_ATTEMPTS
+        ),
+        other => anyhow::anyhow!(
+            "The server responded with an unexpected status code ({})",
+            other
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explain_common_failures() {
+        let got = status_error(25, 404).to_string();
+        assert_eq!(got, "The input for day 25 isn't available yet");
+
+        let got = status_error(1, 400).to_string();
+        assert!(got.contains(SESSION_VARIABLE));
+
+        let got = status_error(1, 500).to_string();
+        assert!(got.contains("500"));
+    }
+
+    #[test]
+    fn back_off_when_rate_limited() {
+        let mut attempts = 0;
+        let mut delays = Vec::new();
+
+        let got = call_with_retries(
+            || {
+                attempts += 1;
+                if attempts < 3 {
+                    Err(429)
+                } else {
+                    Ok(attempts)
+                }
+            },
+            |&status| status == 429,
+            |delay| delays.push(delay),
+        );
+
+        assert_eq!(got.unwrap(), 3);
+        assert_eq!(delays, vec![INITIAL_BACKOFF, INITIAL_BACKOFF * 2]);
+    }
+
+    #[test]
+    fn give_up_after_too_many_attempts() {
+        let mut attempts = 0;
+
+        let got: Result<(), _> = call_with_retries(
+            || {
+                attempts += 1;
+                Err(429)
+            },
+            |&status| status == 429,
+            |_| {},
+        );
+
+        assert_eq!(got, Err(429));
+        assert_eq!(attempts, MAX_ATTEMPTS);
+    }
+
+    #[test]
+    fn other_errors_are_not_retried() {
+        let mut attempts = 0;
+
+        let got: Result<(), _> = call_with_retries(
+            || {
+                attempts += 1;
+                Err(404)
+            },
+            |&status| status == 429,
+            |_| panic!("We shouldn't back off"),
+        );
+
+        assert_eq!(got, Err(404));
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn cached_inputs_are_not_downloaded_again() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(cache_path(dir.path(), 3), "cached").unwrap();
+
+        let got = cached(dir.path(), 3, false, || {
+            panic!("The input shouldn't be downloaded")
+        })
+        .unwrap();
+        assert_eq!(got, "cached");
+
+        let got =
+            cached(dir.path(), 3, true, || Ok(String::from("fresh"))).unwrap();
+        assert_eq!(got, "fresh");
+        assert_eq!(
+            std::fs::read_to_string(cache_path(dir.path(), 3)).unwrap(),
+            "fresh"
+        );
+    }
+
+    #[test]
+    fn missing_inputs_are_saved_to_the_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_dir = dir.path().join("cache");
+
+        let got = cached(&cache_dir, 1, false, || Ok(String::from("1\n2\n")))
+            .unwrap();
+
+        assert_eq!(got, "1\n2\n");
+        assert_eq!(
+            std::fs::read_to_string(cache_path(&cache_dir, 1)).unwrap(),
+            "1\n2\n"
+        );
+    }
+}
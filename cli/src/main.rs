@@ -1,30 +1,160 @@
+mod download;
+mod scaffold;
+
 use anyhow::{Context, Error};
-use std::{fs::File, io::Read, path::PathBuf};
+use aoc_challenges::{parse_number, Challenge};
+use colored::Colorize;
+use flate2::read::GzDecoder;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Cursor, Read, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
+};
 use structopt::StructOpt;
 
 fn main() -> Result<(), Error> {
-    let args = Command::from_args();
-
-    match args {
-        Command::Run { challenge, input } => {
-            let input = match input {
-                Some(filename) => {
-                    let f = File::open(&filename).with_context(|| {
-                        format!("unable to open \"{}\"", filename.display())
-                    })?;
-                    Box::new(f) as Box<dyn Read>
-                }
-                None => Box::new(std::io::stdin()) as Box<dyn Read>,
+    let Args { quiet, command } = Args::from_args();
+    let mut log = Log::new(quiet, std::io::stderr());
+
+    aoc_challenges::validate_unique_challenges()?;
+
+    match command {
+        Command::Run {
+            challenge,
+            input,
+            text,
+            options,
+        } => {
+            let reader = match text {
+                Some(text) => Box::new(Cursor::new(text)) as Box<dyn Read>,
+                None => open_or_stdin(input)?,
             };
 
-            run_challenge(input, &challenge)?;
-        }
-        Command::List => list_challenges(),
+            run_challenge(reader, &challenge, &options, &mut log)?;
+        },
+        Command::Parse { challenge, input } => {
+            parse_input(open_or_stdin(input)?, &challenge)?
+        },
+        Command::RunAll {
+            inputs_dir,
+            parallel,
+        } => run_all(&inputs_dir, parallel, &mut log)?,
+        Command::Bench {
+            challenge,
+            input,
+            iterations,
+        } => bench(&challenge, &input, iterations)?,
+        Command::BenchAll {
+            inputs_dir,
+            iterations,
+        } => print!("{}", bench_all(&inputs_dir, iterations, &mut log)?),
+        Command::Test {
+            challenge,
+            no_color,
+        } => {
+            colored::control::set_override(
+                !no_color && atty::is(atty::Stream::Stdout),
+            );
+            test_challenges(challenge.as_deref())?
+        },
+        Command::Info { challenge } => print_info(&challenge)?,
+        Command::Explain { challenge } => {
+            print!("{}", explain(find_challenge(&challenge)?))
+        },
+        Command::Download {
+            day,
+            output,
+            cache_dir,
+            force,
+        } => download_input(day, output, &cache_dir, force)?,
+        Command::List {
+            tag,
+            grouped,
+            format,
+        } => list_challenges(tag.as_deref(), grouped, format)?,
+        Command::Diff {
+            challenge,
+            input,
+            expected,
+        } => diff(&challenge, &input, &expected)?,
+        Command::New {
+            day,
+            name,
+            challenges_dir,
+        } => {
+            let filename = scaffold::scaffold(&challenges_dir, day, &name)?;
+            println!("Created {}", filename.display());
+        },
+        Command::Auto { input } => auto(&input)?,
+        Command::Summary => print!(
+            "{}",
+            star_summary(with_tag(aoc_challenges::all_challenges(), None))
+        ),
+        Command::Repl { challenge } => {
+            let challenge = find_challenge(&challenge)?;
+            writeln!(
+                log,
+                "Solving {} ({}), finish each input with a blank line",
+                challenge.number, challenge.name
+            )?;
+
+            let stdin = std::io::stdin();
+            repl(
+                stdin.lock(),
+                challenge,
+                &mut std::io::stdout(),
+                &mut std::io::stderr(),
+            )?;
+        },
     }
 
     Ok(())
 }
 
+#[derive(StructOpt)]
+struct Args {
+    #[structopt(
+        short,
+        long,
+        global = true,
+        help = "Only print answers and errors"
+    )]
+    quiet: bool,
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+/// Somewhere to write informational messages (e.g. timings), which are
+/// discarded when `--quiet` is used.
+struct Log<W> {
+    quiet: bool,
+    writer: W,
+}
+
+impl<W: Write> Log<W> {
+    fn new(quiet: bool, writer: W) -> Self {
+        Log { quiet, writer }
+    }
+}
+
+impl<W: Write> Write for Log<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.quiet {
+            Ok(buf.len())
+        } else {
+            self.writer.write(buf)
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
 #[derive(StructOpt)]
 enum Command {
     #[structopt(about = "Run a particular challenge")]
@@ -38,35 +168,1832 @@ enum Command {
             help = "A file to read input from (stdin if not provided)"
         )]
         input: Option<PathBuf>,
+        #[structopt(
+            long,
+            conflicts_with = "input",
+            help = "Use this text as the input instead of reading a file"
+        )]
+        text: Option<String>,
+        #[structopt(flatten)]
+        options: RunOptions,
+    },
+    #[structopt(about = "Check that an input can be parsed without solving")]
+    Parse {
+        #[structopt(help = "The challenge whose input should be parsed")]
+        challenge: String,
+        #[structopt(
+            short,
+            long,
+            parse(from_os_str),
+            help = "A file to read input from (stdin if not provided)"
+        )]
+        input: Option<PathBuf>,
+    },
+    #[structopt(about = "Run every challenge against a directory of inputs")]
+    RunAll {
+        #[structopt(
+            parse(from_os_str),
+            help = "A directory containing \"day_N.txt\" input files"
+        )]
+        inputs_dir: PathBuf,
+        #[structopt(long, help = "Run the challenges concurrently")]
+        parallel: bool,
+    },
+    #[structopt(about = "Time a challenge over many runs")]
+    Bench {
+        #[structopt(help = "The challenge to benchmark")]
+        challenge: String,
+        #[structopt(parse(from_os_str), help = "A file to read input from")]
+        input: PathBuf,
+        #[structopt(
+            short = "n",
+            long,
+            default_value = "100",
+            help = "How many times to run the challenge"
+        )]
+        iterations: usize,
+    },
+    #[structopt(about = "Time every challenge and print a markdown table")]
+    BenchAll {
+        #[structopt(
+            parse(from_os_str),
+            help = "A directory containing \"day_N.txt\" input files"
+        )]
+        inputs_dir: PathBuf,
+        #[structopt(
+            short = "n",
+            long,
+            default_value = "100",
+            help = "How many times to run each challenge"
+        )]
+        iterations: usize,
+    },
+    #[structopt(about = "Check challenges against their examples")]
+    Test {
+        #[structopt(
+            help = "The challenge to test (all challenges if not provided)"
+        )]
+        challenge: Option<String>,
+        #[structopt(long, help = "Don't use colours in the output")]
+        no_color: bool,
+    },
+    #[structopt(about = "Show a challenge's description")]
+    Info {
+        #[structopt(help = "The challenge to describe")]
+        challenge: String,
+    },
+    #[structopt(about = "Show the metadata parsed from a challenge's docs")]
+    Explain {
+        #[structopt(help = "The challenge to explain")]
+        challenge: String,
+    },
+    #[structopt(about = "Download a day's puzzle input")]
+    Download {
+        #[structopt(help = "The day to download")]
+        day: u32,
+        #[structopt(
+            short,
+            long,
+            parse(from_os_str),
+            help = "Where to save the input (\"day_N.txt\" if not provided)"
+        )]
+        output: Option<PathBuf>,
+        #[structopt(
+            long,
+            parse(from_os_str),
+            default_value = ".aoc-cache",
+            help = "Where previously downloaded inputs are kept"
+        )]
+        cache_dir: PathBuf,
+        #[structopt(
+            short,
+            long,
+            help = "Download the input even if it has already been cached"
+        )]
+        force: bool,
     },
     #[structopt(about = "Print all known challenges")]
-    List,
+    List {
+        #[structopt(long, help = "Only show challenges with this tag")]
+        tag: Option<String>,
+        #[structopt(long, help = "Show each day's parts together")]
+        grouped: bool,
+        #[structopt(
+            long,
+            default_value = "text",
+            possible_values = &["text", "json"],
+            help = "How the challenges should be printed"
+        )]
+        format: Format,
+    },
+    #[structopt(about = "Compare a challenge's answer with a known answer")]
+    Diff {
+        #[structopt(help = "The challenge to run")]
+        challenge: String,
+        #[structopt(parse(from_os_str), help = "A file to read input from")]
+        input: PathBuf,
+        #[structopt(
+            parse(from_os_str),
+            help = "A file containing the expected answer"
+        )]
+        expected: PathBuf,
+    },
+    #[structopt(about = "Create a skeleton for a new day's challenges")]
+    New {
+        #[structopt(help = "The day number")]
+        day: u32,
+        #[structopt(help = "The puzzle's name (e.g. \"Report Repair\")")]
+        name: String,
+        #[structopt(
+            long,
+            parse(from_os_str),
+            default_value = "challenges",
+            help = "The challenges crate's directory"
+        )]
+        challenges_dir: PathBuf,
+    },
+    #[structopt(about = "Guess which challenge an input is for and solve it")]
+    Auto {
+        #[structopt(parse(from_os_str), help = "A file to read input from")]
+        input: PathBuf,
+    },
+    #[structopt(about = "Show how many stars have been earned for each day")]
+    Summary,
+    #[structopt(
+        about = "Keep solving inputs from stdin, each ending with a blank line"
+    )]
+    Repl {
+        #[structopt(help = "The challenge to run")]
+        challenge: String,
+    },
+}
+
+#[derive(StructOpt)]
+struct RunOptions {
+    #[structopt(long, help = "Print how long the solution took")]
+    time: bool,
+    #[structopt(long, help = "Run every part of the challenge's day")]
+    both: bool,
+    #[structopt(
+        long,
+        default_value = "text",
+        possible_values = &["text", "json"],
+        help = "How the answer should be printed"
+    )]
+    format: Format,
+    #[structopt(
+        long,
+        help = "Solve line-oriented challenges while their input is being read"
+    )]
+    stdin_until_eof: bool,
+    #[structopt(long, help = "Print the parsed input before solving")]
+    debug_parse: bool,
+    #[structopt(
+        short,
+        long,
+        parse(from_os_str),
+        help = "Write the answer to a file instead of stdout"
+    )]
+    output: Option<PathBuf>,
+    #[structopt(
+        long,
+        value_name = "N",
+        help = "Only solve the first N non-empty lines of the input. This is \
+                for debugging and will usually give the wrong answer"
+    )]
+    sample_lines: Option<usize>,
+    #[structopt(
+        long,
+        help = "Show a progress bar for challenges which report their progress"
+    )]
+    progress: bool,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Format {
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            other => anyhow::bail!("Unknown output format, \"{}\"", other),
+        }
+    }
+}
+
+/// The machine-readable version of a challenge's answer.
+#[derive(Debug, Serialize)]
+struct Answer<'a> {
+    challenge: &'a str,
+    name: &'a str,
+    answer: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    elapsed_ms: Option<f64>,
+}
+
+/// The machine-readable version of a challenge's metadata.
+#[derive(Debug, Serialize)]
+struct ChallengeSummary<'a> {
+    number: &'a str,
+    name: &'a str,
+    year: u16,
+    tags: &'a [&'a str],
+}
+
+fn find_challenge(number: &str) -> Result<&'static Challenge, Error> {
+    lookup(aoc_challenges::all_challenges(), number)
+}
+
+/// Find a challenge by its number, falling back to the only part of a day
+/// when just the day is given (e.g. `"3"` matches `"3a"`).
+///
+/// The number may be prefixed with a year (e.g. `"2020/3a"`) to pick between
+/// challenges from different Advent of Code events.
+fn lookup<'a, I>(challenges: I, number: &str) -> Result<&'a Challenge, Error>
+where
+    I: IntoIterator<Item = &'a Challenge>,
+{
+    let (year, day_and_part) = split_year(number)?;
+    let challenges: Vec<_> = challenges
+        .into_iter()
+        .filter(|c| year.is_none() || year == Some(c.year))
+        .collect();
+
+    let exact: Vec<_> = challenges
+        .iter()
+        .copied()
+        .filter(|c| c.number == day_and_part)
+        .collect();
+
+    match exact.as_slice() {
+        [challenge] => return Ok(challenge),
+        [] => {},
+        _ => return Err(ambiguous(number, exact)),
+    }
+
+    let (day, part) = parse_number(day_and_part);
+    if part.is_some() {
+        anyhow::bail!("Unknown challenge number");
+    }
+
+    let candidates: Vec<_> = challenges
+        .into_iter()
+        .filter(|c| c.matches(day, None))
+        .collect();
+
+    match candidates.as_slice() {
+        [challenge] => Ok(challenge),
+        [] => anyhow::bail!("Unknown challenge number"),
+        _ => Err(ambiguous(number, candidates)),
+    }
+}
+
+/// Split the optional year off a challenge number (e.g. `"2020/3a"`).
+fn split_year(number: &str) -> Result<(Option<u16>, &str), Error> {
+    match number.split_once('/') {
+        Some((year, rest)) => {
+            let year = year
+                .parse()
+                .with_context(|| format!("\"{}\" isn't a valid year", year))?;
+            Ok((Some(year), rest))
+        },
+        None => Ok((None, number)),
+    }
+}
+
+fn ambiguous(number: &str, mut candidates: Vec<&Challenge>) -> Error {
+    candidates.sort_by_key(|c| (c.year, c.parsed_number()));
+    let multiple_years =
+        candidates.iter().any(|c| c.year != candidates[0].year);
+
+    let numbers: Vec<_> = candidates
+        .iter()
+        .map(|c| {
+            if multiple_years {
+                format!("{}/{}", c.year, c.number)
+            } else {
+                c.number.to_string()
+            }
+        })
+        .collect();
+
+    anyhow::anyhow!(
+        "\"{}\" is ambiguous, did you mean one of {}?",
+        number,
+        numbers.join(", ")
+    )
+}
+
+fn open_or_stdin(filename: Option<PathBuf>) -> Result<Box<dyn Read>, Error> {
+    match filename {
+        Some(filename) => open_input(&filename),
+        None => Ok(Box::new(std::io::stdin())),
+    }
+}
+
+/// Open an input file, transparently decompressing it if it has a `.gz`
+/// extension.
+fn open_input(filename: &Path) -> Result<Box<dyn Read>, Error> {
+    let f = File::open(filename).with_context(|| {
+        format!("unable to open \"{}\"", filename.display())
+    })?;
+
+    if filename.extension() == Some("gz".as_ref()) {
+        Ok(Box::new(GzDecoder::new(f)))
+    } else {
+        Ok(Box::new(f))
+    }
+}
+
+fn run_challenge<R: Read>(
+    reader: R,
+    challenge: &str,
+    options: &RunOptions,
+    log: &mut dyn Write,
+) -> Result<(), Error> {
+    let challenges = if options.both {
+        find_parts(challenge)?
+    } else {
+        vec![find_challenge(challenge)?]
+    };
+
+    // the input can only be streamed into one challenge, and only if we don't
+    // need to look at the whole thing first
+    if options.stdin_until_eof
+        && challenges.len() == 1
+        && !options.debug_parse
+        && !options.progress
+        && options.sample_lines.is_none()
+    {
+        return stream_challenge(reader, challenges[0], options, log);
+    }
+
+    let input = if !options.debug_parse
+        && !options.progress
+        && options.sample_lines.is_none()
+        && challenges.iter().all(|c| c.accepts_bytes)
+    {
+        PuzzleInput::Bytes(read_bytes(reader)?)
+    } else {
+        PuzzleInput::Text(read_input(reader)?)
+    };
+
+    let input = match (options.sample_lines, input) {
+        (Some(count), PuzzleInput::Text(text)) => {
+            PuzzleInput::Text(sample_lines(&text, count).to_string())
+        },
+        (_, input) => input,
+    };
+
+    let mut writer = open_output(options)?;
+
+    for challenge in challenges {
+        if let (true, PuzzleInput::Text(text)) = (options.debug_parse, &input) {
+            writeln!(log, "{}", challenge.debug_input(text)?)?;
+        }
+
+        let start = Instant::now();
+        let output = if options.progress {
+            input.solve_with_progress(challenge, log)?
+        } else {
+            input.solve(challenge)?
+        };
+        let elapsed = start.elapsed();
+
+        write_answer(&mut writer, log, challenge, &output, elapsed, options)?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Solve a line-oriented challenge while its input is being read, so only
+/// the parsed lines are kept in memory instead of the entire input.
+///
+/// Challenges which aren't line-oriented still get the whole input, and
+/// reading the input is included in the time taken to solve it.
+fn stream_challenge<R: Read>(
+    reader: R,
+    challenge: &Challenge,
+    options: &RunOptions,
+    log: &mut dyn Write,
+) -> Result<(), Error> {
+    let mut lines = BufReader::new(reader).lines();
+
+    let start = Instant::now();
+    let output = challenge.run_lines(&mut lines)?;
+    let elapsed = start.elapsed();
+
+    let mut writer = open_output(options)?;
+    write_answer(&mut writer, log, challenge, &output, elapsed, options)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn open_output(options: &RunOptions) -> Result<Box<dyn Write>, Error> {
+    match &options.output {
+        Some(filename) => {
+            let f = File::create(filename).with_context(|| {
+                format!("Unable to create \"{}\"", filename.display())
+            })?;
+            Ok(Box::new(f))
+        },
+        None => Ok(Box::new(std::io::stdout())),
+    }
+}
+
+fn write_answer(
+    writer: &mut dyn Write,
+    log: &mut dyn Write,
+    challenge: &Challenge,
+    output: &str,
+    elapsed: Duration,
+    options: &RunOptions,
+) -> Result<(), Error> {
+    let elapsed = if options.time { Some(elapsed) } else { None };
+
+    match options.format {
+        Format::Text => {
+            if options.both {
+                writeln!(writer, "{}: {}", challenge.number, output)?;
+            } else {
+                writeln!(writer, "{}", output)?;
+            }
+
+            if let Some(elapsed) = elapsed {
+                writeln!(log, "solved in {:.1?}", elapsed)?;
+            }
+        },
+        Format::Json => {
+            writeln!(writer, "{}", json_answer(challenge, output, elapsed)?)?
+        },
+    }
+
+    Ok(())
 }
 
-fn run_challenge<R: Read>(mut reader: R, challenge: &str) -> Result<(), Error> {
-    let challenge = aoc_challenges::all_challenges()
-        .find(|c| c.number == challenge)
-        .context("Unknown challenge number")?;
+/// The puzzle input, as either text or raw bytes for challenges which can
+/// skip UTF-8 validation.
+enum PuzzleInput {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl PuzzleInput {
+    fn solve(&self, challenge: &Challenge) -> Result<String, Error> {
+        match self {
+            PuzzleInput::Text(text) => challenge.run(text),
+            PuzzleInput::Bytes(bytes) => challenge.run_bytes(bytes),
+        }
+    }
+
+    /// Solve the challenge, drawing a progress bar on the `log` if the
+    /// challenge reports its progress.
+    fn solve_with_progress(
+        &self,
+        challenge: &Challenge,
+        log: &mut dyn Write,
+    ) -> Result<String, Error> {
+        let text = match self {
+            PuzzleInput::Text(text) => text,
+            PuzzleInput::Bytes(_) => return self.solve(challenge),
+        };
+
+        let mut last_percent = None;
+        let result = challenge.run_with_progress(text, &mut |fraction| {
+            let percent = percentage(fraction);
+
+            if last_percent != Some(percent) {
+                last_percent = Some(percent);
+                // the progress bar is purely informational, so failing to
+                // draw it shouldn't stop the challenge
+                let _ = write!(log, "\r{}", progress_bar(fraction, 40));
+            }
+        });
+
+        if last_percent.is_some() {
+            writeln!(log)?;
+        }
+
+        result
+    }
+}
 
+fn percentage(fraction: f32) -> u32 {
+    (fraction.clamp(0.0, 1.0) * 100.0).round() as u32
+}
+
+/// Draw a progress bar like `[=====>    ]  50%`.
+fn progress_bar(fraction: f32, width: usize) -> String {
+    let filled = (fraction.clamp(0.0, 1.0) * width as f32).round() as usize;
+
+    let mut bar = "=".repeat(filled);
+    if filled < width {
+        bar.push('>');
+        bar.push_str(&" ".repeat(width - filled - 1));
+    }
+
+    format!("[{}] {:>3}%", bar, percentage(fraction))
+}
+
+fn read_bytes<R: Read>(mut reader: R) -> Result<Vec<u8>, Error> {
     let mut input = Vec::new();
     reader
         .read_to_end(&mut input)
         .context("Unable to read the full input")?;
 
-    let input = String::from_utf8(input)
-        .context("Unable to read the input as UTF-8 text")?;
+    Ok(input)
+}
+
+fn read_input<R: Read>(reader: R) -> Result<String, Error> {
+    let input = read_bytes(reader)?;
+
+    String::from_utf8(input).context("Unable to read the input as UTF-8 text")
+}
+
+/// Cut the input off after its first `count` non-empty lines, keeping any
+/// blank lines in between.
+fn sample_lines(input: &str, count: usize) -> &str {
+    let mut remaining = count;
+    let mut end = 0;
+
+    for line in input.split_inclusive('\n') {
+        if remaining == 0 {
+            break;
+        }
+
+        end += line.len();
+        if !line.trim().is_empty() {
+            remaining -= 1;
+        }
+    }
+
+    &input[..end]
+}
+
+fn parse_input<R: Read>(reader: R, challenge: &str) -> Result<(), Error> {
+    let challenge = find_challenge(challenge)?;
+    let input = read_input(reader)?;
+
+    (challenge.validate)(&input).with_context(|| {
+        format!(
+            "Unable to parse the input for challenge {}",
+            challenge.number
+        )
+    })?;
+
+    println!("The input for challenge {} is valid", challenge.number);
+
+    Ok(())
+}
+
+fn json_answer(
+    challenge: &Challenge,
+    output: &str,
+    elapsed: Option<Duration>,
+) -> Result<String, Error> {
+    let answer = Answer {
+        challenge: challenge.number,
+        name: challenge.name,
+        answer: output,
+        elapsed_ms: elapsed.map(|e| e.as_secs_f64() * 1000.0),
+    };
+
+    serde_json::to_string(&answer).context("Unable to serialize the answer")
+}
+
+/// Get every registered part for a particular day, in order.
+fn find_parts(day: &str) -> Result<Vec<&'static Challenge>, Error> {
+    let (year, day) = split_year(day)?;
+    let (day, _) = parse_number(day);
+
+    let parts: Vec<_> = aoc_challenges::all_challenges_sorted()
+        .into_iter()
+        .filter(|c| year.is_none() || year == Some(c.year))
+        .filter(|c| c.matches(day, None))
+        .collect();
+
+    if parts.is_empty() {
+        anyhow::bail!("No challenges found for day {}", day);
+    }
+
+    Ok(parts)
+}
+
+fn run_all(
+    inputs_dir: &Path,
+    parallel: bool,
+    log: &mut dyn Write,
+) -> Result<(), Error> {
+    let mut failures = 0;
+
+    for (challenge, result) in solve_all(inputs_dir, parallel, log)? {
+        match result {
+            Ok(output) => {
+                println!(
+                    "{}: {} => {}",
+                    challenge.number, challenge.name, output
+                )
+            },
+            Err(e) => {
+                eprintln!("Error: {:?}", e);
+                failures += 1;
+            },
+        }
+    }
+
+    if failures > 0 {
+        anyhow::bail!("{} challenge(s) failed", failures);
+    }
+
+    Ok(())
+}
+
+/// A challenge and the result of running it.
+type Outcome = (&'static Challenge, Result<String, Error>);
+
+/// Solve every challenge with an input in `inputs_dir`, returning the results
+/// in the same order as [`aoc_challenges::all_challenges_sorted()`].
+fn solve_all(
+    inputs_dir: &Path,
+    parallel: bool,
+    log: &mut dyn Write,
+) -> Result<Vec<Outcome>, Error> {
+    let mut jobs = Vec::new();
+
+    for challenge in aoc_challenges::all_challenges_sorted() {
+        match find_input(inputs_dir, challenge.number) {
+            Some(filename) => jobs.push((challenge, filename)),
+            None => writeln!(
+                log,
+                "Warning: No input found for challenge {}",
+                challenge.number
+            )?,
+        }
+    }
+
+    let solve = |(challenge, filename): &(&'static Challenge, PathBuf)| {
+        let result = std::fs::read_to_string(filename)
+            .with_context(|| {
+                format!("unable to read \"{}\"", filename.display())
+            })
+            .and_then(|input| challenge.run(&input));
+
+        (*challenge, result)
+    };
+
+    if parallel {
+        // challenges are plain functions without any shared state, and
+        // collecting an indexed parallel iterator keeps the original order
+        Ok(jobs.par_iter().map(solve).collect())
+    } else {
+        Ok(jobs.iter().map(solve).collect())
+    }
+}
+
+fn diff(challenge: &str, input: &Path, expected: &Path) -> Result<(), Error> {
+    let challenge = find_challenge(challenge)?;
 
-    let output = (challenge.solve)(&input)?;
-    println!("{}", output);
+    let input = read_input(open_input(input)?)?;
+    let expected = std::fs::read_to_string(expected).with_context(|| {
+        format!("unable to read \"{}\"", expected.display())
+    })?;
+
+    let got = challenge.run(&input)?;
+
+    match compare_answers(&got, &expected) {
+        None => {
+            println!("match");
+            Ok(())
+        },
+        Some(differences) => {
+            print!("{}", differences);
+            anyhow::bail!(
+                "The answer to {} didn't match the expected answer",
+                challenge.number
+            );
+        },
+    }
+}
+
+/// Solve every input read from `reader` until EOF, where each input is
+/// terminated by a blank line. Errors are reported without stopping, so one
+/// bad input doesn't end the session.
+fn repl<R: BufRead>(
+    reader: R,
+    challenge: &Challenge,
+    answers: &mut dyn Write,
+    errors: &mut dyn Write,
+) -> Result<(), Error> {
+    let mut input = String::new();
+
+    for line in reader.lines() {
+        let line = line.context("Unable to read the input")?;
+
+        if !line.trim().is_empty() {
+            input.push_str(&line);
+            input.push('\n');
+        } else if !input.is_empty() {
+            repl_once(challenge, &input, answers, errors)?;
+            input.clear();
+        }
+    }
+
+    if !input.is_empty() {
+        repl_once(challenge, &input, answers, errors)?;
+    }
+
+    Ok(())
+}
+
+fn repl_once(
+    challenge: &Challenge,
+    input: &str,
+    answers: &mut dyn Write,
+    errors: &mut dyn Write,
+) -> Result<(), Error> {
+    match challenge.run(input) {
+        Ok(answer) => {
+            writeln!(answers, "{}", answer)?;
+            answers.flush()?;
+        },
+        Err(e) => writeln!(errors, "Error: {:?}", e)?,
+    }
 
     Ok(())
 }
 
-fn list_challenges() {
-    let mut challenges: Vec<_> = aoc_challenges::all_challenges().collect();
-    challenges.sort_by_key(|c| c.number);
+/// Compare an answer with the expected answer, ignoring leading and trailing
+/// whitespace, and show the differences if they aren't the same.
+fn compare_answers(got: &str, expected: &str) -> Option<String> {
+    let got = got.trim();
+    let expected = expected.trim();
+
+    if got == expected {
+        return None;
+    }
+
+    let mut differences = String::new();
+
+    for line in expected.lines() {
+        differences.push_str(&format!("- {}\n", line));
+    }
+    for line in got.lines() {
+        differences.push_str(&format!("+ {}\n", line));
+    }
+
+    Some(differences)
+}
+
+fn bench(
+    challenge: &str,
+    filename: &Path,
+    iterations: usize,
+) -> Result<(), Error> {
+    let challenge = find_challenge(challenge)?;
+
+    let mut input = String::new();
+    open_input(filename)?
+        .read_to_string(&mut input)
+        .with_context(|| {
+            format!("unable to read \"{}\"", filename.display())
+        })?;
+
+    let Timings {
+        answer,
+        min,
+        mean,
+        max,
+    } = benchmark(challenge, &input, iterations)?;
+
+    println!("{}", answer);
+    println!(
+        "min: {:.1?}, mean: {:.1?}, max: {:.1?} ({} iterations)",
+        min, mean, max, iterations
+    );
+
+    Ok(())
+}
+
+/// Benchmark every challenge with an input in `inputs_dir`, returning a
+/// markdown table with each challenge's mean time.
+fn bench_all(
+    inputs_dir: &Path,
+    iterations: usize,
+    log: &mut dyn Write,
+) -> Result<String, Error> {
+    let mut means = Vec::new();
+
+    for challenge in aoc_challenges::all_challenges_sorted() {
+        let filename = match find_input(inputs_dir, challenge.number) {
+            Some(filename) => filename,
+            None => {
+                writeln!(
+                    log,
+                    "Warning: No input found for challenge {}",
+                    challenge.number
+                )?;
+                continue;
+            },
+        };
+
+        let input = std::fs::read_to_string(&filename).with_context(|| {
+            format!("unable to read \"{}\"", filename.display())
+        })?;
+        let timings = benchmark(challenge, &input, iterations)?;
+        means.push((challenge, timings.mean));
+    }
+
+    Ok(markdown_table(&means))
+}
+
+fn markdown_table(means: &[(&Challenge, Duration)]) -> String {
+    let mut table = String::from(
+        "| Challenge | Name | Mean Time |\n|-----------|------|-----------|\n",
+    );
+
+    for (challenge, mean) in means {
+        table.push_str(&format!(
+            "| {} | {} | {:.1?} |\n",
+            challenge.number, challenge.name, mean
+        ));
+    }
+
+    table
+}
+
+/// How long a challenge took to solve over several runs.
+#[derive(Debug, Clone, PartialEq)]
+struct Timings {
+    answer: String,
+    min: Duration,
+    mean: Duration,
+    max: Duration,
+}
+
+fn benchmark(
+    challenge: &Challenge,
+    input: &str,
+    iterations: usize,
+) -> Result<Timings, Error> {
+    anyhow::ensure!(iterations > 0, "At least one iteration is required");
+
+    let mut answer: Option<String> = None;
+    let mut times = Vec::with_capacity(iterations);
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let output = challenge.run(input)?;
+        times.push(start.elapsed());
+
+        match &answer {
+            Some(previous) if *previous != output => anyhow::bail!(
+                "The answer changed between runs (\"{}\" vs \"{}\")",
+                previous,
+                output
+            ),
+            Some(_) => {},
+            None => answer = Some(output),
+        }
+    }
+
+    let total: Duration = times.iter().sum();
+
+    Ok(Timings {
+        answer: answer.expect("There is at least one iteration"),
+        min: times.iter().copied().min().unwrap_or_default(),
+        mean: total / iterations as u32,
+        max: times.iter().copied().max().unwrap_or_default(),
+    })
+}
+
+/// Find the input file for a challenge, preferring a part-specific file (e.g.
+/// `day_4a.txt`) and falling back to the one for the whole day (`day_4.txt`).
+fn find_input(inputs_dir: &Path, number: &str) -> Option<PathBuf> {
+    let (day, _) = parse_number(number);
+    let candidates =
+        [format!("day_{}.txt", number), format!("day_{}.txt", day)];
+
+    candidates
+        .iter()
+        .map(|filename| inputs_dir.join(filename))
+        .find(|path| path.is_file())
+}
+
+/// The challenges to check, resolving a challenge number the same way as
+/// `run` does.
+fn challenges_to_test(
+    challenge: Option<&str>,
+) -> Result<Vec<&'static Challenge>, Error> {
+    match challenge {
+        Some(number) => Ok(vec![find_challenge(number)?]),
+        None => Ok(aoc_challenges::all_challenges_sorted()),
+    }
+}
+
+fn test_challenges(challenge: Option<&str>) -> Result<(), Error> {
+    let challenges = challenges_to_test(challenge)?;
+
+    let mut passed = 0;
+    let mut failed = 0;
+
+    for challenge in challenges {
+        for (i, example) in challenge.examples.iter().enumerate() {
+            let name = format!("{} example {}", challenge.number, i + 1);
+
+            match (challenge.solve)(example.input) {
+                Ok(actual) if actual.trim() == example.expected.trim() => {
+                    println!("{} ... {}", name, "ok".green());
+                    passed += 1;
+                },
+                Ok(actual) => {
+                    println!(
+                        "{} ... {} (expected \"{}\", got \"{}\")",
+                        name,
+                        "FAILED".red(),
+                        example.expected,
+                        actual
+                    );
+                    failed += 1;
+                },
+                Err(e) => {
+                    println!("{} ... {} ({})", name, "FAILED".red(), e);
+                    failed += 1;
+                },
+            }
+        }
+    }
+
+    println!();
+    println!("test result: {} passed, {} failed", passed, failed);
+
+    if failed > 0 {
+        anyhow::bail!("{} example(s) failed", failed);
+    }
+
+    Ok(())
+}
+
+fn print_info(challenge: &str) -> Result<(), Error> {
+    let challenge = find_challenge(challenge)?;
+    let width = textwrap::termwidth();
+
+    println!("Day {}: {}", challenge.number, challenge.name);
+
+    for block in challenge.description.split("\n\n") {
+        println!();
+
+        if block.contains('\n') {
+            // multi-line blocks are code snippets and shouldn't be re-wrapped
+            println!("{}", block);
+        } else {
+            println!("{}", textwrap::fill(block, width));
+        }
+    }
+
+    if challenge.example_count() > 0 {
+        println!();
+        println!("Examples ({}):", challenge.example_count());
+    }
+
+    for (i, example) in challenge.examples.iter().enumerate() {
+        println!();
+        println!("Example {} input:", i + 1);
+        println!("{}", example.input);
+        println!("Expected: {}", example.expected);
+    }
+
+    Ok(())
+}
+
+/// Show the raw metadata for a challenge, exactly as it was parsed.
+fn explain(challenge: &Challenge) -> String {
+    let (day, part) = challenge.parsed_number();
+    let part = part.map(String::from).unwrap_or_else(|| "-".to_string());
+
+    let fields = [
+        ("year", challenge.year.to_string()),
+        ("number", format!("{:?}", challenge.number)),
+        ("day", day.to_string()),
+        ("part", part),
+        ("name", format!("{:?}", challenge.name)),
+        ("tags", format!("{:?}", challenge.tags)),
+        ("stars", challenge.stars.to_string()),
+        ("line oriented", challenge.line_oriented.to_string()),
+        ("examples", challenge.example_count().to_string()),
+        ("description", format!("{:?}", challenge.description)),
+    ];
+
+    fields
+        .iter()
+        .map(|(label, value)| {
+            format!("{:<14} {}\n", format!("{}:", label), value)
+        })
+        .collect()
+}
+
+fn download_input(
+    day: u32,
+    output: Option<PathBuf>,
+    cache_dir: &Path,
+    force: bool,
+) -> Result<(), Error> {
+    let input = download::cached(cache_dir, day, force, || {
+        let session =
+            std::env::var(download::SESSION_VARIABLE).with_context(|| {
+                format!(
+                    "The {} environment variable should contain your session token",
+                    download::SESSION_VARIABLE
+                )
+            })?;
+
+        download::download(day, &session)
+    })?;
+
+    let output =
+        output.unwrap_or_else(|| PathBuf::from(format!("day_{}.txt", day)));
+    std::fs::write(&output, input).with_context(|| {
+        format!("unable to save to \"{}\"", output.display())
+    })?;
+
+    Ok(())
+}
+
+fn list_challenges(
+    tag: Option<&str>,
+    grouped: bool,
+    format: Format,
+) -> Result<(), Error> {
+    let challenges = with_tag(aoc_challenges::all_challenges(), tag);
+
+    if format == Format::Json {
+        println!("{}", json_challenges(&challenges)?);
+        return Ok(());
+    }
+
+    if !grouped {
+        for challenge in challenges {
+            println!(
+                "{}/{}: {}",
+                challenge.year, challenge.number, challenge.name
+            );
+        }
+        return Ok(());
+    }
+
+    for (day, parts) in group_by_day(challenges) {
+        println!(
+            "{} Day {}: {}",
+            parts[0].year,
+            day,
+            day_title(parts[0].name)
+        );
+
+        for part in parts {
+            match part.parsed_number() {
+                (_, Some(letter)) => println!("  {}: {}", letter, part.name),
+                (_, None) => println!("  {}", part.name),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn json_challenges(challenges: &[&Challenge]) -> Result<String, Error> {
+    let summaries: Vec<_> = challenges
+        .iter()
+        .map(|c| ChallengeSummary {
+            number: c.number,
+            name: c.name,
+            year: c.year,
+            tags: c.tags,
+        })
+        .collect();
+
+    serde_json::to_string(&summaries)
+        .context("Unable to serialize the challenges")
+}
+
+fn auto(filename: &Path) -> Result<(), Error> {
+    let input = read_input(open_input(filename)?)?;
+
+    for challenge in detect(aoc_challenges::all_challenges_sorted(), &input)? {
+        println!("{}: {}", challenge.number, challenge.run(&input)?);
+    }
+
+    Ok(())
+}
+
+/// Find the parts of the day whose heuristics recognise the input, complaining
+/// if it looks like it could be for more than one day.
+fn detect<'a>(
+    challenges: Vec<&'a Challenge>,
+    input: &str,
+) -> Result<Vec<&'a Challenge>, Error> {
+    let matches: Vec<_> = challenges
+        .into_iter()
+        .filter(|c| (c.detect)(input))
+        .collect();
+    let mut days = group_by_day(matches);
+
+    match days.len() {
+        0 => anyhow::bail!("Unable to tell which challenge the input is for"),
+        1 => Ok(days.remove(0).1),
+        _ => {
+            let candidates: Vec<_> = days
+                .iter()
+                .map(|(day, parts)| format!("{} day {}", parts[0].year, day))
+                .collect();
+            anyhow::bail!(
+                "The input could be for any of {}",
+                candidates.join(", ")
+            )
+        },
+    }
+}
+
+/// Show the stars earned for each day, followed by the total.
+fn star_summary(challenges: Vec<&Challenge>) -> String {
+    let mut summary = String::new();
+    let mut total = 0;
+
+    for (day, parts) in group_by_day(challenges) {
+        let stars: u32 = parts.iter().map(|c| u32::from(c.stars)).sum();
+        total += stars;
+
+        summary.push_str(&format!(
+            "{} Day {:>2}: {:<4} {}\n",
+            parts[0].year,
+            day,
+            "*".repeat(stars as usize),
+            day_title(parts[0].name)
+        ));
+    }
+
+    summary.push_str(&format!("Total: {} stars\n", total));
+    summary
+}
+
+/// Group challenges which are already sorted by year and number into their
+/// days.
+fn group_by_day<'a>(
+    challenges: Vec<&'a Challenge>,
+) -> Vec<(u32, Vec<&'a Challenge>)> {
+    let mut days: Vec<(u32, Vec<&'a Challenge>)> = Vec::new();
 
     for challenge in challenges {
-        println!("{}: {}", challenge.number, challenge.name);
+        let (day, _) = challenge.parsed_number();
+
+        match days.last_mut() {
+            Some((last_day, parts))
+                if *last_day == day && parts[0].year == challenge.year =>
+            {
+                parts.push(challenge)
+            },
+            _ => days.push((day, vec![challenge])),
+        }
+    }
+
+    days
+}
+
+/// Get a day's title from one of its challenges (e.g. `"Report Repair"` from
+/// `"Report Repair (part 1)"`).
+fn day_title(name: &str) -> &str {
+    match name.rfind(" (part") {
+        Some(index) if name.ends_with(')') => &name[..index],
+        _ => name,
+    }
+}
+
+/// Get the challenges with a particular tag (or all of them if no tag is
+/// provided), sorted by number.
+fn with_tag<'a, I>(challenges: I, tag: Option<&str>) -> Vec<&'a Challenge>
+where
+    I: IntoIterator<Item = &'a Challenge>,
+{
+    let mut challenges: Vec<_> = challenges
+        .into_iter()
+        .filter(|c| tag.is_none() || c.tags.iter().any(|t| Some(*t) == tag))
+        .collect();
+    challenges.sort_by_key(|c| (c.year, c.parsed_number()));
+
+    challenges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_with_inline_text() {
+        let args = [
+            "aoc-cli",
+            "run",
+            "2a",
+            "--text",
+            "1-3 a: abcde\n1-3 b: cdefg\n",
+        ];
+
+        let text = match Command::from_iter_safe(&args).unwrap() {
+            Command::Run { text, input, .. } => {
+                assert!(input.is_none());
+                text.unwrap()
+            },
+            _ => unreachable!(),
+        };
+
+        let options = RunOptions {
+            time: false,
+            both: false,
+            format: Format::Text,
+            stdin_until_eof: false,
+            debug_parse: false,
+            output: None,
+            sample_lines: None,
+            progress: false,
+        };
+        run_challenge(Cursor::new(text), "2a", &options, &mut std::io::sink())
+            .unwrap();
+    }
+
+    #[test]
+    fn write_the_answer_to_a_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let filename = dir.path().join("answer.txt");
+        let args = ["aoc-cli", "run", "2a", "--output", "answer.txt", "--both"];
+        let mut options = match Command::from_iter_safe(&args).unwrap() {
+            Command::Run { options, .. } => options,
+            _ => unreachable!(),
+        };
+        assert_eq!(options.output, Some(PathBuf::from("answer.txt")));
+        options.output = Some(filename.clone());
+        let input = "1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n";
+
+        run_challenge(Cursor::new(input), "2a", &options, &mut Vec::new())
+            .unwrap();
+
+        let got = std::fs::read_to_string(&filename).unwrap();
+        assert_eq!(got, "2a: 2\n2b: 1\n");
+    }
+
+    #[test]
+    fn only_solve_a_sample_of_the_input() {
+        let input =
+            "1-3 a: abcde\n1-3 b: cdefg\n\n2-9 c: ccccccccc\n1-1 z: z\n";
+
+        assert_eq!(
+            sample_lines(input, 3),
+            "1-3 a: abcde\n1-3 b: cdefg\n\n2-9 c: ccccccccc\n"
+        );
+        assert_eq!(sample_lines(input, 0), "");
+        assert_eq!(sample_lines(input, 100), input);
+
+        let dir = tempfile::tempdir().unwrap();
+        let filename = dir.path().join("answer.txt");
+        let args = ["aoc-cli", "run", "2a", "--sample-lines", "3"];
+        let mut options = match Command::from_iter_safe(&args).unwrap() {
+            Command::Run { options, .. } => options,
+            _ => unreachable!(),
+        };
+        assert_eq!(options.sample_lines, Some(3));
+        options.output = Some(filename.clone());
+
+        run_challenge(Cursor::new(input), "2a", &options, &mut Vec::new())
+            .unwrap();
+
+        let got = std::fs::read_to_string(&filename).unwrap();
+        assert_eq!(got, "2\n");
+    }
+
+    #[test]
+    fn test_and_run_select_the_same_challenges() {
+        for number in &["4a", "2020/4a", "4", "2019/4a", "nope"] {
+            let tested = challenges_to_test(Some(number))
+                .map(|found| found.iter().map(|c| c.number).collect::<Vec<_>>())
+                .map_err(|e| e.to_string());
+            let run = find_challenge(number)
+                .map(|c| vec![c.number])
+                .map_err(|e| e.to_string());
+
+            assert_eq!(tested, run, "{}", number);
+        }
+
+        assert_eq!(
+            challenges_to_test(None).unwrap().len(),
+            aoc_challenges::all_challenges().count()
+        );
+    }
+
+    #[test]
+    fn draw_a_progress_bar() {
+        assert_eq!(progress_bar(0.5, 10), "[=====>    ]  50%");
+        assert_eq!(progress_bar(0.0, 4), "[>   ]   0%");
+        assert_eq!(progress_bar(1.0, 4), "[====] 100%");
+        assert_eq!(progress_bar(1.5, 4), "[====] 100%");
+    }
+
+    #[test]
+    fn show_progress_while_solving() {
+        let dir = tempfile::tempdir().unwrap();
+        let filename = dir.path().join("answer.txt");
+        let args = ["aoc-cli", "run", "1b", "--progress"];
+        let mut options = match Command::from_iter_safe(&args).unwrap() {
+            Command::Run { options, .. } => options,
+            _ => unreachable!(),
+        };
+        assert!(options.progress);
+        options.output = Some(filename.clone());
+        let input = "1721\n979\n366\n299\n675\n1456\n";
+        let mut log = Vec::new();
+
+        run_challenge(Cursor::new(input), "1b", &options, &mut log).unwrap();
+
+        let got = std::fs::read_to_string(&filename).unwrap();
+        assert_eq!(got, "241861950\n");
+        let log = String::from_utf8(log).unwrap();
+        assert!(log.starts_with("\r[>"));
+        assert!(log.ends_with("100%\n"));
+
+        // challenges without progress reporting are solved normally
+        let mut log = Vec::new();
+        run_challenge(Cursor::new(input), "1a", &options, &mut log).unwrap();
+        assert_eq!(std::fs::read_to_string(&filename).unwrap(), "514579\n");
+        assert!(log.is_empty());
+    }
+
+    #[test]
+    fn compare_answers_ignoring_whitespace() {
+        assert_eq!(compare_answers("42", "42\n"), None);
+        assert_eq!(compare_answers(" 42 ", "\n42\r\n"), None);
+        assert_eq!(compare_answers("41", "42\n").unwrap(), "- 42\n+ 41\n");
+    }
+
+    #[test]
+    fn explain_the_parsed_metadata() {
+        let challenge = find_challenge("2a").unwrap();
+
+        let got = explain(challenge);
+
+        assert!(got.contains("name:          \"Password Philosophy"));
+        assert!(got.contains("day:           2\n"));
+        assert!(got.contains("part:          a\n"));
+        assert!(got.contains("description:   \"Your flight departs"));
+    }
+
+    #[test]
+    fn show_the_parsed_input() {
+        let challenge = find_challenge("2a").unwrap();
+
+        let got = challenge.debug_input("1-3 a: abcde").unwrap();
+
+        assert!(got.starts_with("Lines(\n"), "{}", got);
+        assert!(got.contains("Input {"), "{}", got);
+        assert!(got.contains("password: \"abcde\""), "{}", got);
+        assert!(challenge.debug_input("1-3 a").is_err());
+    }
+
+    #[test]
+    fn solve_line_oriented_input_while_reading_it() {
+        let challenge = find_challenge("2a").unwrap();
+        assert!(challenge.line_oriented);
+        assert!(challenge.solve_lines.is_some());
+        let example = challenge.examples[0];
+        let messy_input = example.input.replace('\n', "\r\n\r\n");
+
+        let got = challenge
+            .run_lines(&mut Cursor::new(&messy_input).lines())
+            .unwrap();
+
+        assert_eq!(got, example.expected);
+    }
+
+    #[test]
+    fn stream_the_input_into_a_challenge() {
+        let dir = tempfile::tempdir().unwrap();
+        let filename = dir.path().join("answer.txt");
+        let args = ["aoc-cli", "run", "2b", "--stdin-until-eof"];
+        let mut options = match Command::from_iter_safe(&args).unwrap() {
+            Command::Run { options, .. } => options,
+            _ => unreachable!(),
+        };
+        options.output = Some(filename.clone());
+        let input = "1-3 a: abcde\n\n1-3 b: cdefg\r\n2-9 c: ccccccccc\n";
+        let mut log = Vec::new();
+
+        run_challenge(Cursor::new(input), "2b", &options, &mut log).unwrap();
+
+        assert_eq!(std::fs::read_to_string(&filename).unwrap(), "1\n");
+
+        // challenges which aren't line oriented get the whole input
+        let input = "abc\n\na\nb\nc\n\nab\nac\n\na\na\na\na\n\nb";
+        run_challenge(Cursor::new(input), "6a", &options, &mut log).unwrap();
+
+        assert_eq!(std::fs::read_to_string(&filename).unwrap(), "11\n");
+    }
+
+    #[test]
+    fn text_and_input_files_are_mutually_exclusive() {
+        let args = ["aoc-cli", "run", "2a", "--text", "1", "--input", "x.txt"];
+
+        assert!(Command::from_iter_safe(&args).is_err());
+    }
+
+    #[test]
+    fn find_every_part_of_a_day() {
+        let parts: Vec<_> =
+            find_parts("4").unwrap().iter().map(|c| c.number).collect();
+
+        assert_eq!(parts, &["4a", "4b"]);
+        assert!(find_parts("42").is_err());
+    }
+
+    #[test]
+    fn json_answers_only_include_the_time_when_requested() {
+        let challenge = find_challenge("3a").unwrap();
+
+        let got = json_answer(challenge, "7", None).unwrap();
+        assert_eq!(
+            got,
+            r#"{"challenge":"3a","name":"Toboggan Trajectory (part 1)","answer":"7"}"#
+        );
+
+        let got =
+            json_answer(challenge, "7", Some(Duration::from_micros(2100)))
+                .unwrap();
+        assert!(got.ends_with(r#""answer":"7","elapsed_ms":2.1}"#));
+    }
+
+    #[test]
+    fn list_challenges_as_json() {
+        let challenges = with_tag(aoc_challenges::all_challenges(), None);
+
+        let got = json_challenges(&challenges).unwrap();
+
+        let got: serde_json::Value = serde_json::from_str(&got).unwrap();
+        let day_3 = got
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["number"] == "3a")
+            .unwrap();
+        assert_eq!(
+            *day_3,
+            serde_json::json!({
+                "number": "3a",
+                "name": "Toboggan Trajectory (part 1)",
+                "year": 2020,
+                "tags": ["grid"],
+            })
+        );
+    }
+
+    fn challenge(
+        number: &'static str,
+        tags: &'static [&'static str],
+    ) -> Challenge {
+        Challenge {
+            tags,
+            ..Challenge::placeholder(number)
+        }
+    }
+
+    #[test]
+    fn filter_challenges_by_tag() {
+        let challenges = vec![
+            challenge("10", &["grid"]),
+            challenge("2", &["grid", "parsing"]),
+            challenge("3", &["math"]),
+            challenge("4", &[]),
+        ];
+
+        let numbers = |tag| -> Vec<_> {
+            with_tag(&challenges, tag)
+                .iter()
+                .map(|c| c.number)
+                .collect()
+        };
+
+        assert_eq!(numbers(Some("grid")), &["2", "10"]);
+        assert_eq!(numbers(Some("parsing")), &["2"]);
+        assert!(numbers(Some("strings")).is_empty());
+        assert_eq!(numbers(None), &["2", "3", "4", "10"]);
+    }
+
+    #[test]
+    fn group_challenges_by_day() {
+        let challenges = [
+            challenge("1a", &[]),
+            challenge("1b", &[]),
+            challenge("2", &[]),
+            challenge("4b", &[]),
+        ];
+
+        let got: Vec<(u32, Vec<_>)> = group_by_day(challenges.iter().collect())
+            .into_iter()
+            .map(|(day, parts)| {
+                (day, parts.into_iter().map(|c| c.number).collect())
+            })
+            .collect();
+
+        assert_eq!(
+            got,
+            vec![(1, vec!["1a", "1b"]), (2, vec!["2"]), (4, vec!["4b"])]
+        );
+    }
+
+    #[test]
+    fn strip_the_part_from_a_challenge_name() {
+        assert_eq!(day_title("Report Repair (part 1)"), "Report Repair");
+        assert_eq!(day_title("Passport Processing"), "Passport Processing");
+    }
+
+    #[test]
+    fn look_up_a_challenge_by_its_day() {
+        let challenges = vec![
+            challenge("3a", &[]),
+            challenge("4a", &[]),
+            challenge("4b", &[]),
+        ];
+
+        assert_eq!(lookup(&challenges, "4b").unwrap().number, "4b");
+        assert_eq!(lookup(&challenges, "3").unwrap().number, "3a");
+        assert!(lookup(&challenges, "3b").is_err());
+        assert!(lookup(&challenges, "5").is_err());
+    }
+
+    #[test]
+    fn ambiguous_days_list_the_candidates() {
+        let challenges = vec![
+            challenge("4b", &[]),
+            challenge("3a", &[]),
+            challenge("4a", &[]),
+        ];
+
+        let err = lookup(&challenges, "4").unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "\"4\" is ambiguous, did you mean one of 4a, 4b?"
+        );
+    }
+
+    #[test]
+    fn benchmark_results_are_a_markdown_table() {
+        let first = Challenge {
+            name: "Report Repair",
+            ..challenge("1a", &[])
+        };
+        let second = Challenge {
+            name: "Password Philosophy",
+            ..challenge("2b", &[])
+        };
+
+        let got = markdown_table(&[
+            (&first, Duration::from_micros(1500)),
+            (&second, Duration::from_millis(12)),
+        ]);
+
+        assert_eq!(
+            got,
+            "| Challenge | Name | Mean Time |\n\
+             |-----------|------|-----------|\n\
+             | 1a | Report Repair | 1.5ms |\n\
+             | 2b | Password Philosophy | 12.0ms |\n"
+        );
+    }
+
+    #[test]
+    fn benchmark_every_challenge_with_an_input() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("day_2.txt"),
+            "1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n",
+        )
+        .unwrap();
+        let mut log = Vec::new();
+
+        let got = bench_all(dir.path(), 2, &mut log).unwrap();
+
+        let rows: Vec<_> = got.lines().skip(2).collect();
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].starts_with("| 2a | "));
+        assert!(rows[1].starts_with("| 2b | "));
+        let log = String::from_utf8(log).unwrap();
+        assert!(log.contains("No input found for challenge 1a"));
+    }
+
+    #[test]
+    fn detect_each_days_example_input() {
+        for day in 1..=6 {
+            let part_1 = find_parts(&day.to_string()).unwrap()[0];
+            let example = part_1.examples[0].input;
+
+            let got = detect(aoc_challenges::all_challenges_sorted(), example)
+                .unwrap();
+
+            let days: Vec<_> =
+                got.iter().map(|c| c.parsed_number().0).collect();
+            assert_eq!(days, vec![day, day], "{:?}", example);
+        }
+    }
+
+    #[test]
+    fn inputs_matching_several_days_are_ambiguous() {
+        let anything = |number| Challenge {
+            detect: |_| true,
+            ..challenge(number, &[])
+        };
+        let challenges = [anything("1a"), challenge("2a", &[]), anything("3")];
+
+        let err = detect(challenges.iter().collect(), "").unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "The input could be for any of 2020 day 1, 2020 day 3"
+        );
+        assert!(detect(vec![&challenges[1]], "").is_err());
+    }
+
+    #[test]
+    fn total_the_stars_for_each_day() {
+        let with_stars = |number, name, stars| Challenge {
+            name,
+            stars,
+            ..challenge(number, &[])
+        };
+        let challenges = [
+            with_stars("1a", "Report Repair (part 1)", 1),
+            with_stars("1b", "Report Repair (part 2)", 1),
+            with_stars("2a", "Password Philosophy (part 1)", 1),
+            with_stars("2b", "Password Philosophy (part 2)", 0),
+            with_stars("13", "Shuttle Search", 0),
+        ];
+
+        let got = star_summary(challenges.iter().collect());
+
+        assert_eq!(
+            got,
+            "2020 Day  1: **   Report Repair\n\
+             2020 Day  2: *    Password Philosophy\n\
+             2020 Day 13:      Shuttle Search\n\
+             Total: 3 stars\n"
+        );
+    }
+
+    #[test]
+    fn the_repl_keeps_going_after_errors() {
+        let doubler = Challenge {
+            solve: |input| {
+                let n: u32 = input.trim().parse()?;
+                Ok((n * 2).to_string())
+            },
+            ..challenge("1", &[])
+        };
+        let input = "21\n\n\nnope\n\n5\n";
+        let mut answers = Vec::new();
+        let mut errors = Vec::new();
+
+        repl(input.as_bytes(), &doubler, &mut answers, &mut errors).unwrap();
+
+        assert_eq!(String::from_utf8(answers).unwrap(), "42\n10\n");
+        let errors = String::from_utf8(errors).unwrap();
+        assert_eq!(
+            errors.lines().filter(|l| l.starts_with("Error")).count(),
+            1
+        );
+        assert!(errors.contains("invalid digit"));
+    }
+
+    #[test]
+    fn pick_a_challenge_from_a_particular_year() {
+        let challenges = vec![
+            challenge("1a", &[]),
+            Challenge {
+                year: 2021,
+                ..challenge("1a", &[])
+            },
+        ];
+
+        assert_eq!(lookup(&challenges, "2020/1a").unwrap().year, 2020);
+        assert_eq!(lookup(&challenges, "2021/1").unwrap().year, 2021);
+        assert!(lookup(&challenges, "2019/1a").is_err());
+        assert!(lookup(&challenges, "twenty/1a").is_err());
+
+        let err = lookup(&challenges, "1a").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "\"1a\" is ambiguous, did you mean one of 2020/1a, 2021/1a?"
+        );
+    }
+
+    #[test]
+    fn benchmark_a_challenge() {
+        let challenge = find_challenge("1a").unwrap();
+        let example = challenge.examples[0];
+
+        let got = benchmark(challenge, example.input, 5).unwrap();
+
+        assert_eq!(got.answer, example.expected);
+        assert!(got.min <= got.mean && got.mean <= got.max);
+        assert!(benchmark(challenge, example.input, 0).is_err());
+    }
+
+    #[test]
+    fn gzipped_inputs_are_decompressed() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let challenge = find_challenge("1a").unwrap();
+        let input = challenge.examples[0].input;
+
+        let plain = dir.join("day_1.txt");
+        std::fs::write(&plain, input).unwrap();
+        let gzipped = dir.join("day_1.txt.gz");
+        let mut encoder = GzEncoder::new(
+            File::create(&gzipped).unwrap(),
+            Compression::default(),
+        );
+        encoder.write_all(input.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+
+        let read = |path: &Path| {
+            let mut text = String::new();
+            open_input(path).unwrap().read_to_string(&mut text).unwrap();
+            challenge.run(&text).unwrap()
+        };
+
+        assert_eq!(read(&gzipped), read(&plain));
+        assert_eq!(read(&gzipped), challenge.examples[0].expected);
+    }
+
+    #[test]
+    fn quiet_mode_hides_timings() {
+        let args = ["aoc-cli", "run", "--time", "--quiet", "2a", "--text", ""];
+        let Args { quiet, command } = Args::from_iter_safe(&args).unwrap();
+        let options = match command {
+            Command::Run { options, .. } => options,
+            _ => unreachable!(),
+        };
+        assert!(quiet && options.time);
+        let input = "1-3 a: abcde\n";
+
+        let mut log = Log::new(quiet, Vec::new());
+        run_challenge(Cursor::new(input), "2a", &options, &mut log).unwrap();
+        assert!(log.writer.is_empty());
+
+        let mut log = Log::new(false, Vec::new());
+        run_challenge(Cursor::new(input), "2a", &options, &mut log).unwrap();
+        let stderr = String::from_utf8(log.writer).unwrap();
+        assert!(stderr.starts_with("solved in"), "{}", stderr);
+    }
+
+    #[test]
+    fn solve_all_in_parallel() {
+        let dir = tempfile::tempdir().unwrap();
+        for day in &["2", "5", "6"] {
+            let challenge = find_parts(day).unwrap()[0];
+            let filename = dir.path().join(format!("day_{}.txt", day));
+            std::fs::write(filename, challenge.examples[0].input).unwrap();
+        }
+        let answers = |parallel| -> Vec<_> {
+            solve_all(dir.path(), parallel, &mut std::io::sink())
+                .unwrap()
+                .into_iter()
+                .map(|(c, result)| {
+                    (c.number, result.map_err(|e| e.to_string()))
+                })
+                .collect()
+        };
+
+        let sequential = answers(false);
+        let parallel = answers(true);
+
+        assert_eq!(sequential, parallel);
+        let numbers: Vec<_> = sequential.iter().map(|(n, _)| *n).collect();
+        assert_eq!(numbers, &["2a", "2b", "5a", "5b", "6a", "6b"]);
+    }
+
+    #[test]
+    fn inputs_fall_back_to_the_whole_day() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        std::fs::write(dir.join("day_4.txt"), "").unwrap();
+        std::fs::write(dir.join("day_5b.txt"), "").unwrap();
+
+        assert_eq!(find_input(dir, "4a"), Some(dir.join("day_4.txt")));
+        assert_eq!(find_input(dir, "5b"), Some(dir.join("day_5b.txt")));
+        assert_eq!(find_input(dir, "5a"), None);
     }
 }
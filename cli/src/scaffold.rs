@@ -0,0 +1,160 @@
+use anyhow::{Context, Error};
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// The skeleton used for a new `day_N.rs` file, where `{day}` and `{name}`
+/// get replaced with the day number and puzzle name.
+const TEMPLATE: &str = r#"use anyhow::Error;
+
+/// Day {day}a: {name} (part 1)
+///
+/// # Description
+///
+/// TODO: copy the puzzle description from adventofcode.com
+#[aoc_macros::challenge]
+pub fn part_1(_input: &str) -> Result<usize, Error> {
+    Ok(0)
+}
+
+/// Day {day}b: {name} (part 2)
+///
+/// # Description
+///
+/// TODO: copy the puzzle description from adventofcode.com
+#[aoc_macros::challenge]
+pub fn part_2(_input: &str) -> Result<usize, Error> {
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_example() {
+        // TODO: use the example from the puzzle description
+        assert_eq!(part_1("").unwrap(), 0);
+    }
+}
+"#;
+
+/// Generate the source code for a new day.
+pub fn render(day: u32, name: &str) -> String {
+    TEMPLATE
+        .replace("{day}", &day.to_string())
+        .replace("{name}", name)
+}
+
+/// Create `day_N.rs` inside the challenges crate's `src/` directory and
+/// declare it in `lib.rs`, refusing to overwrite an existing file.
+pub fn scaffold(
+    challenges_dir: &Path,
+    day: u32,
+    name: &str,
+) -> Result<PathBuf, Error> {
+    if !name.starts_with(|c: char| c.is_uppercase()) {
+        anyhow::bail!(
+            "The challenge name should start with an uppercase letter, not \"{}\"",
+            name
+        );
+    }
+
+    let src = challenges_dir.join("src");
+    let lib_rs = src.join("lib.rs");
+    anyhow::ensure!(
+        lib_rs.is_file(),
+        "\"{}\" doesn't exist, is \"{}\" the challenges crate?",
+        lib_rs.display(),
+        challenges_dir.display()
+    );
+
+    let filename = src.join(format!("day_{}.rs", day));
+    let mut f = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&filename)
+        .with_context(|| {
+            format!("Unable to create \"{}\"", filename.display())
+        })?;
+    f.write_all(render(day, name).as_bytes()).with_context(|| {
+        format!("Unable to write to \"{}\"", filename.display())
+    })?;
+
+    let declarations = fs::read_to_string(&lib_rs)
+        .with_context(|| format!("Unable to read \"{}\"", lib_rs.display()))?;
+    fs::write(&lib_rs, declare_module(&declarations, day)).with_context(
+        || format!("Unable to update \"{}\"", lib_rs.display()),
+    )?;
+
+    Ok(filename)
+}
+
+/// Add a `pub mod day_N;` line after the last day's declaration.
+fn declare_module(lib_rs: &str, day: u32) -> String {
+    let declaration = format!("pub mod day_{};", day);
+    let mut lines: Vec<&str> = lib_rs.lines().collect();
+
+    if lines.iter().any(|line| line.trim() == declaration) {
+        return lib_rs.to_string();
+    }
+
+    let position = lines
+        .iter()
+        .rposition(|line| line.starts_with("pub mod day_"))
+        .map(|ix| ix + 1)
+        .unwrap_or(0);
+    lines.insert(position, &declaration);
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    updated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_has_headers_for_both_parts() {
+        let got = render(7, "Handy Haversacks");
+
+        assert!(got.contains("/// Day 7a: Handy Haversacks (part 1)\n"));
+        assert!(got.contains("/// Day 7b: Handy Haversacks (part 2)\n"));
+        assert_eq!(got.matches("#[aoc_macros::challenge]").count(), 2);
+    }
+
+    #[test]
+    fn declare_the_new_module_after_the_others() {
+        let lib_rs = "pub mod day_1;\npub mod day_2;\n\npub use foo::Bar;\n";
+
+        let got = declare_module(lib_rs, 3);
+
+        assert_eq!(
+            got,
+            "pub mod day_1;\npub mod day_2;\npub mod day_3;\n\npub use foo::Bar;\n"
+        );
+        assert_eq!(declare_module(&got, 3), got);
+    }
+
+    #[test]
+    fn never_overwrite_an_existing_day() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        fs::create_dir(&src).unwrap();
+        fs::write(src.join("lib.rs"), "pub mod day_1;\n").unwrap();
+
+        let filename = scaffold(dir.path(), 2, "Password Philosophy").unwrap();
+
+        assert_eq!(filename, src.join("day_2.rs"));
+        assert_eq!(
+            fs::read_to_string(src.join("lib.rs")).unwrap(),
+            "pub mod day_1;\npub mod day_2;\n"
+        );
+        fs::write(&filename, "// my solution").unwrap();
+        assert!(scaffold(dir.path(), 2, "Password Philosophy").is_err());
+        assert_eq!(fs::read_to_string(&filename).unwrap(), "// my solution");
+    }
+}
@@ -0,0 +1,42 @@
+use std::fmt::Display;
+
+/// Something which can be returned as the answer to a challenge.
+///
+/// This is implemented for everything that implements [`Display`], so most
+/// challenges can return a number or string. Challenges with a more complex
+/// answer can implement it on their own type to control the formatting.
+pub trait Answer {
+    fn format(&self) -> String;
+}
+
+impl<T: Display + ?Sized> Answer for T {
+    fn format(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Letters(Vec<char>);
+
+    impl Answer for Letters {
+        fn format(&self) -> String {
+            self.0.iter().collect()
+        }
+    }
+
+    #[test]
+    fn displayable_types_are_answers() {
+        assert_eq!(42_u64.format(), "42");
+        assert_eq!("Hello".format(), "Hello");
+    }
+
+    #[test]
+    fn custom_answer() {
+        let letters = Letters(vec!['a', 'b', 'c']);
+
+        assert_eq!(letters.format(), "abc");
+    }
+}
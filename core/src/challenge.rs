@@ -1,5 +1,14 @@
-use anyhow::Error;
-use std::fmt::{self, Debug, Formatter};
+use anyhow::{Context, Error};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Debug, Formatter},
+    io,
+};
+
+/// The year used for challenges that don't specify one.
+pub const DEFAULT_YEAR: u16 = 2020;
 
 #[derive(Debug, Copy, Clone)]
 pub struct Example {
@@ -13,32 +22,451 @@ pub fn all_challenges() -> impl Iterator<Item = &'static Challenge> {
     inventory::iter::<Challenge>.into_iter()
 }
 
+/// Parse the `Day 4a: Passport Processing` header from a challenge's
+/// doc-comment, returning the day, name, and (optional) part.
+///
+/// The header may appear anywhere in the doc-comment, and the name must start
+/// with a capital letter.
+pub fn parse_challenge_header(
+    docs: &str,
+) -> Result<(String, String, Option<char>), String> {
+    static PATTERN: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"(?im)day (\d+)([a-z]?)\s*:\s*([^\n]+)$").unwrap()
+    });
+
+    let captures = PATTERN.captures(docs).ok_or_else(|| {
+        String::from(
+            r#"Unable to determine the challenge name and day. Expected something like "Day 1: Report Repair""#,
+        )
+    })?;
+
+    let day = captures[1].to_string();
+    let part = captures[2].chars().next().map(|c| c.to_ascii_lowercase());
+    let name = captures[3].trim().to_string();
+
+    if !name.starts_with(|c: char| c.is_uppercase()) {
+        return Err(format!(
+            "The challenge name should start with a capital letter, found \"{}\"",
+            name
+        ));
+    }
+
+    Ok((day, name, part))
+}
+
+/// Get all the registered challenges, sorted by year, day, and then part.
+pub fn all_challenges_sorted() -> Vec<&'static Challenge> {
+    let mut challenges: Vec<_> = all_challenges().collect();
+    challenges.sort_by_key(|c| (c.year, c.parsed_number()));
+
+    challenges
+}
+
+/// Make sure no two challenges were registered with the same year and number.
+///
+/// The `inventory` crate only collects challenges at runtime, so this should be
+/// called on startup.
+pub fn validate_unique_challenges() -> Result<(), Error> {
+    ensure_unique(all_challenges())
+}
+
+fn ensure_unique<'a, I>(challenges: I) -> Result<(), Error>
+where
+    I: IntoIterator<Item = &'a Challenge>,
+{
+    let mut names: BTreeMap<(u16, &str), Vec<&str>> = BTreeMap::new();
+
+    for challenge in challenges {
+        names
+            .entry((challenge.year, challenge.number))
+            .or_default()
+            .push(challenge.name);
+    }
+
+    let duplicates: Vec<_> = names
+        .into_iter()
+        .filter(|(_, names)| names.len() > 1)
+        .map(|((year, number), names)| {
+            format!("{}/{} ({})", year, number, names.join(", "))
+        })
+        .collect();
+
+    anyhow::ensure!(
+        duplicates.is_empty(),
+        "Multiple challenges were registered with the same number: {}",
+        duplicates.join("; ")
+    );
+
+    Ok(())
+}
+
+/// Split a challenge number like `"10b"` into its day and part, so challenges
+/// can be sorted in their natural order (i.e. `"2a"` comes before `"10"`).
+///
+/// Malformed numbers are given a day of `u32::MAX` so they sort after
+/// everything else.
+pub fn parse_number(number: &str) -> (u32, Option<char>) {
+    let end_of_day = number
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(number.len());
+    let (day, part) = number.split_at(end_of_day);
+
+    let mut letters = part.chars();
+
+    match (day.parse(), letters.next(), letters.next()) {
+        (Ok(day), None, _) => (day, None),
+        (Ok(day), Some(letter), None) if letter.is_ascii_alphabetic() => {
+            (day, Some(letter.to_ascii_lowercase()))
+        },
+        _ => (u32::MAX, None),
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct Challenge {
+    /// The Advent of Code event this challenge is from (e.g. `2020`).
+    pub year: u16,
     pub number: &'static str,
     pub name: &'static str,
     pub description: &'static str,
     pub examples: &'static [Example],
+    /// Keywords for grouping similar challenges (e.g. `"grid"` or `"parsing"`).
+    pub tags: &'static [&'static str],
+    /// How many stars have been earned for this challenge (0, 1, or 2).
+    pub stars: u8,
+    /// Does the challenge parse its input one line at a time (e.g. with
+    /// `Lines<T>`), ignoring blank lines?
+    pub line_oriented: bool,
+    /// Can the challenge parse its input straight from bytes, skipping UTF-8
+    /// validation?
+    pub accepts_bytes: bool,
+    /// A heuristic for guessing whether an input is for this challenge.
+    pub detect: fn(&str) -> bool,
     pub solve: fn(&str) -> Result<String, Error>,
+    /// Solve the challenge using raw bytes. If the challenge doesn't
+    /// [accept bytes](Challenge::accepts_bytes) the input is checked for
+    /// valid UTF-8 and passed to the normal [`Challenge::solve`].
+    pub solve_bytes: fn(&[u8]) -> Result<String, Error>,
+    /// Parse the input without solving the challenge, useful for checking
+    /// whether an input is well-formed.
+    pub validate: fn(&str) -> Result<(), Error>,
+    /// Parse the input and pretty-print it with `{:#?}`, so you can see what
+    /// the challenge will be working with.
+    pub debug: fn(&str) -> Result<String, Error>,
+    /// Solve the challenge while reporting how far through it is (from `0.0`
+    /// to `1.0`). Challenges without this are solved with
+    /// [`Challenge::solve`] instead.
+    pub solve_with_progress: Option<SolveWithProgress>,
+    /// Solve the challenge while its input is being read, parsing each line
+    /// as it arrives. Only [line oriented](Challenge::line_oriented)
+    /// challenges have this.
+    pub solve_lines: Option<SolveLines>,
+}
+
+/// A function which solves a challenge, calling the callback with how far
+/// through it is.
+pub type SolveWithProgress =
+    fn(&str, &mut dyn FnMut(f32)) -> Result<String, Error>;
+
+/// A function which solves a challenge, reading its input one line at a time.
+pub type SolveLines =
+    fn(&mut dyn Iterator<Item = io::Result<String>>) -> Result<String, Error>;
+
+impl Challenge {
+    /// A challenge which accepts any input and does nothing, useful as a
+    /// starting point for tests.
+    #[doc(hidden)]
+    pub fn placeholder(number: &'static str) -> Challenge {
+        Challenge {
+            year: DEFAULT_YEAR,
+            number,
+            name: "",
+            description: "",
+            examples: &[],
+            tags: &[],
+            stars: 0,
+            line_oriented: false,
+            accepts_bytes: false,
+            detect: |_| false,
+            solve: |_| Ok(String::new()),
+            solve_bytes: |_| Ok(String::new()),
+            validate: |_| Ok(()),
+            debug: |_| Ok(String::new()),
+            solve_with_progress: None,
+            solve_lines: None,
+        }
+    }
+
+    /// The challenge's day and part (e.g. `"4a"` is `(4, Some('a'))`).
+    pub fn parsed_number(&self) -> (u32, Option<char>) {
+        parse_number(self.number)
+    }
+
+    /// Check whether this challenge is for a particular day and, if provided,
+    /// a particular part of that day.
+    pub fn matches(&self, day: u32, part: Option<char>) -> bool {
+        let (our_day, our_part) = self.parsed_number();
+
+        our_day == day && (part.is_none() || part == our_part)
+    }
+
+    /// How many examples the challenge can be checked against.
+    pub fn example_count(&self) -> usize {
+        self.examples.len()
+    }
+
+    /// Solve the challenge using the input's raw bytes.
+    pub fn run_bytes(&self, input: &[u8]) -> Result<String, Error> {
+        (self.solve_bytes)(input).with_context(|| {
+            format!("Unable to solve challenge {}", self.number)
+        })
+    }
+
+    /// Parse the input and show its intermediate representation.
+    pub fn debug_input(&self, input: &str) -> Result<String, Error> {
+        (self.debug)(input).with_context(|| {
+            format!("Unable to parse the input for challenge {}", self.number)
+        })
+    }
+
+    /// Solve the challenge for a particular input.
+    pub fn run(&self, input: &str) -> Result<String, Error> {
+        (self.solve)(input).with_context(|| {
+            format!("Unable to solve challenge {}", self.number)
+        })
+    }
+
+    /// Solve the challenge, passing `progress` updates along if the challenge
+    /// [supports them](Challenge::solve_with_progress).
+    pub fn run_with_progress(
+        &self,
+        input: &str,
+        progress: &mut dyn FnMut(f32),
+    ) -> Result<String, Error> {
+        match self.solve_with_progress {
+            Some(solve) => solve(input, progress).with_context(|| {
+                format!("Unable to solve challenge {}", self.number)
+            }),
+            None => self.run(input),
+        }
+    }
+
+    /// Solve the challenge while its input is being read, if it
+    /// [supports that](Challenge::solve_lines). Otherwise every line is read
+    /// into memory and the challenge is [run](Challenge::run) as normal.
+    pub fn run_lines(
+        &self,
+        lines: &mut dyn Iterator<Item = io::Result<String>>,
+    ) -> Result<String, Error> {
+        match self.solve_lines {
+            Some(solve) => solve(lines).with_context(|| {
+                format!("Unable to solve challenge {}", self.number)
+            }),
+            None => {
+                let mut input = String::new();
+
+                for line in lines {
+                    input.push_str(&line.context("Unable to read the input")?);
+                    input.push('\n');
+                }
+
+                self.run(&input)
+            },
+        }
+    }
 }
 
 inventory::collect!(Challenge);
 
+/// The `(year, number)` of every registered challenge.
+///
+/// Each `#[aoc_macros::challenge]` and [`register_solver!()`] adds a `#[used]`
+/// static to a dedicated link section, so (unlike [`all_challenges()`]) this
+/// table is assembled by the linker and can be checked for duplicates without
+/// constructing any challenges.
+///
+/// [`register_solver!()`]: crate::register_solver
+#[linkme::distributed_slice]
+pub static CHALLENGE_NUMBERS: [(u16, &'static str)] = [..];
+
 impl Debug for Challenge {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let Challenge {
+            year,
             number: day,
             name,
             description,
             examples,
+            tags,
             ..
         } = self;
 
         f.debug_struct("Challenge")
+            .field("year", year)
             .field("day", day)
             .field("name", name)
             .field("description", description)
             .field("examples", examples)
+            .field("tags", tags)
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn challenge(number: &'static str, name: &'static str) -> Challenge {
+        Challenge {
+            name,
+            ..Challenge::placeholder(number)
+        }
+    }
+
+    #[test]
+    fn parse_challenge_numbers() {
+        let inputs = vec![
+            ("1", (1, None)),
+            ("4a", (4, Some('a'))),
+            ("25b", (25, Some('b'))),
+            ("12B", (12, Some('b'))),
+            ("", (u32::MAX, None)),
+            ("a", (u32::MAX, None)),
+            ("4ab", (u32::MAX, None)),
+            ("4-", (u32::MAX, None)),
+        ];
+
+        for (number, should_be) in inputs {
+            assert_eq!(parse_number(number), should_be, "{}", number);
+        }
+    }
+
+    #[test]
+    fn parse_headers() {
+        let inputs = vec![
+            (
+                "Day 4a: Passport Processing",
+                ("4", "Passport Processing", Some('a')),
+            ),
+            ("day 12B: Rain Risk", ("12", "Rain Risk", Some('b'))),
+            ("Day 25: Combo Breaker ", ("25", "Combo Breaker", None)),
+            (
+                "Some text\n\nDay 1: Report Repair\n",
+                ("1", "Report Repair", None),
+            ),
+        ];
+
+        for (docs, (day, name, part)) in inputs {
+            let got = parse_challenge_header(docs).unwrap();
+
+            assert_eq!(got, (day.to_string(), name.to_string(), part));
+        }
+    }
+
+    #[test]
+    fn invalid_headers() {
+        let inputs =
+            vec!["", "Report Repair", "Day x: Report Repair", "Day 6b: ass"];
+
+        for docs in inputs {
+            assert!(parse_challenge_header(docs).is_err(), "{}", docs);
+        }
+    }
+
+    #[test]
+    fn challenges_are_sorted_by_day_then_part() {
+        let mut numbers = vec!["10", "2b", "1", "2", "10a", "2a"];
+
+        numbers.sort_by_key(|n| parse_number(n));
+
+        assert_eq!(numbers, &["1", "2", "2a", "2b", "10", "10a"]);
+    }
+
+    inventory::submit! { challenge("10", "Ten") }
+    inventory::submit! { challenge("2b", "Two B") }
+    inventory::submit! { challenge("2a", "Two A") }
+
+    #[test]
+    fn registered_challenges_can_be_sorted() {
+        let numbers: Vec<_> =
+            all_challenges_sorted().iter().map(|c| c.number).collect();
+
+        assert_eq!(numbers, &["2a", "2b", "10"]);
+    }
+
+    #[test]
+    fn match_by_day_and_part() {
+        let challenge = challenge("4a", "Passports");
+
+        assert!(challenge.matches(4, None));
+        assert!(challenge.matches(4, Some('a')));
+        assert!(!challenge.matches(4, Some('b')));
+        assert!(!challenge.matches(5, None));
+    }
+
+    #[test]
+    fn count_the_examples() {
+        let mut with_examples = challenge("1a", "Report Repair");
+        with_examples.examples = &[
+            Example {
+                input: "1721\n299",
+                expected: "514579",
+            },
+            Example {
+                input: "1010\n1010",
+                expected: "1020100",
+            },
+        ];
+
+        assert_eq!(with_examples.example_count(), 2);
+        assert_eq!(challenge("1b", "Report Repair").example_count(), 0);
+    }
+
+    #[test]
+    fn errors_mention_the_challenge_number() {
+        let mut failing = challenge("4a", "Passports");
+        failing.solve = |_| Err(Error::msg("Oops"));
+
+        let err = failing.run("").unwrap_err();
+
+        assert_eq!(format!("{:#}", err), "Unable to solve challenge 4a: Oops");
+    }
+
+    #[test]
+    fn unique_challenges_are_fine() {
+        let challenges =
+            vec![challenge("1a", "First"), challenge("1b", "Second")];
+
+        ensure_unique(&challenges).unwrap();
+    }
+
+    #[test]
+    fn detect_duplicate_challenges() {
+        let challenges = vec![
+            challenge("4a", "Passports"),
+            challenge("1a", "Report Repair"),
+            challenge("4a", "Copy-Pasted"),
+            challenge("4b", "Passports"),
+        ];
+
+        let err = ensure_unique(&challenges).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Multiple challenges were registered with the same number: 2020/4a (Passports, Copy-Pasted)"
+        );
+    }
+
+    #[test]
+    fn the_same_number_can_be_used_in_different_years() {
+        let challenges = vec![
+            challenge("1a", "Report Repair"),
+            Challenge {
+                year: 2021,
+                ..challenge("1a", "Sonar Sweep")
+            },
+        ];
+
+        ensure_unique(&challenges).unwrap();
+    }
+}
@@ -1,14 +1,20 @@
-use std::{convert::TryFrom, ops::Deref, str::FromStr};
+use anyhow::{Context, Error};
+use std::{
+    collections::VecDeque, convert::TryFrom, iter::FromIterator, ops::Deref,
+    slice, str::FromStr,
+};
 
 /// A specialised input for handling lists of items, where each item is on its
 /// own line.
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct Lines<T>(pub Vec<T>);
 
 impl<T> FromStr for Lines<T>
 where
     T: FromStr,
+    T::Err: Into<Error>,
 {
-    type Err = T::Err;
+    type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Lines::try_from(s)
@@ -18,21 +24,86 @@ where
 impl<'input, T> TryFrom<&'input str> for Lines<T>
 where
     T: FromStr,
+    T::Err: Into<Error>,
 {
-    type Error = T::Err;
+    type Error = Error;
 
     fn try_from(s: &'input str) -> Result<Self, Self::Error> {
+        parse_lines(s, is_item).map(Lines)
+    }
+}
+
+impl<T> Lines<T> {
+    /// Parse each line like [`Lines::try_from()`], also skipping comments
+    /// (lines starting with `prefix` once leading whitespace is removed).
+    ///
+    /// ```rust
+    /// # use aoc_core::Lines;
+    /// let src = "# the first number\n1\n  # the second number\n2\n";
+    ///
+    /// let lines = Lines::<u32>::parse_ignoring_comments(src, "#").unwrap();
+    ///
+    /// assert_eq!(lines.0, vec![1, 2]);
+    /// ```
+    pub fn parse_ignoring_comments(s: &str, prefix: &str) -> Result<Self, Error>
+    where
+        T: FromStr,
+        T::Err: Into<Error>,
+    {
+        parse_lines(s, |line| {
+            let is_comment =
+                !prefix.is_empty() && line.trim_start().starts_with(prefix);
+            is_item(line) && !is_comment
+        })
+        .map(Lines)
+    }
+
+    /// Parse lines as they are read (e.g. from [`std::io::BufRead::lines()`]),
+    /// skipping blank lines.
+    ///
+    /// Only the parsed items are kept, so the raw text never needs to be held
+    /// in memory all at once.
+    ///
+    /// ```rust
+    /// # use aoc_core::Lines;
+    /// use std::io::BufRead;
+    ///
+    /// let reader = std::io::Cursor::new("1\r\n\r\n2\r\n");
+    ///
+    /// let lines = Lines::<u32>::from_lines(reader.lines()).unwrap();
+    ///
+    /// assert_eq!(lines.0, vec![1, 2]);
+    /// ```
+    pub fn from_lines<I, E>(lines: I) -> Result<Self, Error>
+    where
+        T: FromStr,
+        T::Err: Into<Error>,
+        I: IntoIterator<Item = Result<String, E>>,
+        E: Into<Error>,
+    {
         let mut items = Vec::new();
 
-        for line in s.lines() {
-            if !line.is_empty() {
-                let item = line.trim().parse()?;
-                items.push(item);
+        for (i, line) in lines.into_iter().enumerate() {
+            let line = line
+                .map_err(Into::into)
+                .with_context(|| format!("Unable to read line {}", i + 1))?;
+
+            if is_item(&line) {
+                items.push(parse_line(&line, i + 1)?);
             }
         }
 
         Ok(Lines(items))
     }
+
+    /// Iterate over the parsed lines.
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.0.iter()
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.0.push(item);
+    }
 }
 
 impl<T> Deref for Lines<T> {
@@ -52,23 +123,250 @@ impl<T> IntoIterator for Lines<T> {
     }
 }
 
+impl<'a, T> IntoIterator for &'a Lines<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<T> Extend<T> for Lines<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.0.extend(iter);
+    }
+}
+
+impl<T> FromIterator<T> for Lines<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Lines(iter.into_iter().collect())
+    }
+}
+
+/// Like [`Lines`], except blank lines are kept and passed to `T`'s parser
+/// instead of being skipped.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RawLines<T>(pub Vec<T>);
+
+impl<T> FromStr for RawLines<T>
+where
+    T: FromStr,
+    T::Err: Into<Error>,
+{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RawLines::try_from(s)
+    }
+}
+
+impl<'input, T> TryFrom<&'input str> for RawLines<T>
+where
+    T: FromStr,
+    T::Err: Into<Error>,
+{
+    type Error = Error;
+
+    fn try_from(s: &'input str) -> Result<Self, Self::Error> {
+        parse_lines(s, |_| true).map(RawLines)
+    }
+}
+
+impl<T> Deref for RawLines<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> IntoIterator for RawLines<T> {
+    type Item = T;
+    type IntoIter = <Vec<T> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// Parse every line accepted by the `filter`, attaching the line number to
+/// any errors.
+fn parse_lines<T, F>(s: &str, filter: F) -> Result<Vec<T>, Error>
+where
+    T: FromStr,
+    T::Err: Into<Error>,
+    F: Fn(&str) -> bool,
+{
+    let mut items = Vec::new();
+
+    for (i, line) in s.lines().enumerate() {
+        if filter(line) {
+            items.push(parse_line(line, i + 1)?);
+        }
+    }
+
+    Ok(items)
+}
+
+/// Should a line be parsed into an item by [`Lines`], rather than skipped?
+///
+/// This is shared by every way of creating a [`Lines`] so they always skip
+/// the same lines.
+fn is_item(line: &str) -> bool {
+    !line.is_empty()
+}
+
+fn parse_line<T>(line: &str, line_number: usize) -> Result<T, Error>
+where
+    T: FromStr,
+    T::Err: Into<Error>,
+{
+    let line = line.trim();
+
+    line.parse::<T>().map_err(Into::into).with_context(|| {
+        format!("Unable to parse line {}: \"{}\"", line_number, line)
+    })
+}
+
+/// A specialised input for a list of items separated by commas (e.g.
+/// `"3,4,3,1,2"`). Surrounding whitespace and empty fields are ignored.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CommaSeparated<T>(pub Vec<T>);
+
+impl<T> FromStr for CommaSeparated<T>
+where
+    T: FromStr,
+{
+    type Err = T::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CommaSeparated::try_from(s)
+    }
+}
+
+impl<'input, T> TryFrom<&'input str> for CommaSeparated<T>
+where
+    T: FromStr,
+{
+    type Error = T::Err;
+
+    fn try_from(s: &'input str) -> Result<Self, Self::Error> {
+        let mut items = Vec::new();
+
+        for field in s.split(',') {
+            let field = field.trim();
+
+            if !field.is_empty() {
+                items.push(field.parse()?);
+            }
+        }
+
+        Ok(CommaSeparated(items))
+    }
+}
+
+impl<T> Deref for CommaSeparated<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> IntoIterator for CommaSeparated<T> {
+    type Item = T;
+    type IntoIter = <Vec<T> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// A list of items separated by any amount of whitespace (spaces, tabs, or
+/// newlines).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Numbers<T>(pub Vec<T>);
+
+impl<T> FromStr for Numbers<T>
+where
+    T: FromStr,
+    T::Err: Into<Error>,
+{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Numbers::try_from(s)
+    }
+}
+
+impl<'input, T> TryFrom<&'input str> for Numbers<T>
+where
+    T: FromStr,
+    T::Err: Into<Error>,
+{
+    type Error = Error;
+
+    fn try_from(s: &'input str) -> Result<Self, Self::Error> {
+        s.split_whitespace()
+            .enumerate()
+            .map(|(i, token)| {
+                token.parse().map_err(Into::into).with_context(|| {
+                    format!("Unable to parse item {}: \"{}\"", i + 1, token)
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()
+            .map(Numbers)
+    }
+}
+
+impl<T> Deref for Numbers<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> IntoIterator for Numbers<T> {
+    type Item = T;
+    type IntoIter = <Vec<T> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// An iterator over groups of lines, where each group is separated by one or
+/// more blank lines.
+///
+/// Cloning is cheap, so a copy can be used to look ahead (e.g. with
+/// [`count_groups()`]) without consuming the original.
+#[derive(Debug, Clone)]
 pub struct GroupedLines<'input>(std::str::Lines<'input>);
 
-impl<'input> TryFrom<&'input str> for GroupedLines<'input> {
-    type Error = std::convert::Infallible;
+/// Count how many groups [`GroupedLines`] would yield for an input.
+pub fn count_groups(input: &str) -> usize {
+    GroupedLines::from(input).count()
+}
 
-    fn try_from(value: &'input str) -> Result<Self, Self::Error> {
-        Ok(GroupedLines(value.lines()))
+impl<'input> From<&'input str> for GroupedLines<'input> {
+    fn from(value: &'input str) -> Self {
+        GroupedLines(value.lines())
     }
 }
 
 impl<'input> Iterator for GroupedLines<'input> {
-    type Item = Vec<&'input str>;
+    type Item = Group<'input>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let mut group = Vec::new();
 
-        while let Some(line) = self.0.next() {
+        for line in self.0.by_ref() {
+            // str::lines() only strips a single "\r\n", so stray carriage
+            // returns need to be removed before checking for blank lines
+            let line = line.trim_end_matches('\r');
+
             if group.is_empty() && line.is_empty() {
                 continue;
             } else if line.is_empty() {
@@ -81,7 +379,895 @@ impl<'input> Iterator for GroupedLines<'input> {
         if group.is_empty() {
             None
         } else {
-            Some(group)
+            Some(Group(group))
+        }
+    }
+}
+
+/// A single group of non-blank lines yielded by [`GroupedLines`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Group<'input>(Vec<&'input str>);
+
+impl<'input> Group<'input> {
+    pub fn lines(&self) -> &[&'input str] {
+        &self.0
+    }
+
+    /// The group's text, with each line separated by a newline.
+    pub fn joined(&self) -> String {
+        self.0.join("\n")
+    }
+
+    /// The group's text with each line separated by a space, useful when
+    /// records may be split across multiple lines.
+    pub fn as_str_with_spaces(&self) -> String {
+        self.0.join(" ")
+    }
+}
+
+impl<'input> IntoIterator for Group<'input> {
+    type Item = &'input str;
+    type IntoIter = <Vec<&'input str> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// Blocks of text separated by one or more blank lines.
+///
+/// Unlike [`GroupedLines`], each paragraph is a single slice of the original
+/// input, so any newlines inside a paragraph are preserved.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Paragraphs<'input>(pub Vec<&'input str>);
+
+impl<'input> From<&'input str> for Paragraphs<'input> {
+    fn from(value: &'input str) -> Self {
+        let mut paragraphs = Vec::new();
+        let mut start = None;
+        let mut end = 0;
+        let mut offset = 0;
+
+        for line in value.split('\n') {
+            let content = line.strip_suffix('\r').unwrap_or(line);
+
+            if content.is_empty() {
+                if let Some(start) = start.take() {
+                    paragraphs.push(&value[start..end]);
+                }
+            } else {
+                start.get_or_insert(offset);
+                end = offset + content.len();
+            }
+
+            offset += line.len() + 1;
+        }
+
+        if let Some(start) = start {
+            paragraphs.push(&value[start..end]);
+        }
+
+        Paragraphs(paragraphs)
+    }
+}
+
+impl<'input> Deref for Paragraphs<'input> {
+    type Target = Vec<&'input str>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'input> IntoIterator for Paragraphs<'input> {
+    type Item = &'input str;
+    type IntoIter = <Vec<&'input str> as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// A rectangular grid of cells, where each cell is parsed from a single
+/// character.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+// implemented by hand so empty grids don't need `T: Default`
+impl<T> Default for Grid<T> {
+    fn default() -> Self {
+        Grid {
+            cells: Vec::new(),
+            width: 0,
+            height: 0,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(width * height, cells.len());
+
+        Grid {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// Create a grid from its rows, making sure they all have the same
+    /// length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, Error> {
+        let width = rows.first().context("The grid can't be empty")?.len();
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+
+        for (i, row) in rows.into_iter().enumerate() {
+            anyhow::ensure!(
+                row.len() == width,
+                "The grid should be {} items wide but row {} had {} items",
+                width,
+                i + 1,
+                row.len()
+            );
+            cells.extend(row);
+        }
+
+        Ok(Grid::new(width, height, cells))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, column: usize, row: usize) -> Option<&T> {
+        if column < self.width && row < self.height {
+            self.cells.get(column + row * self.width)
+        } else {
+            None
+        }
+    }
+
+    /// Get a cell, treating the grid as if it repeats infinitely to the
+    /// right.
+    pub fn get_wrapping(&self, column: usize, row: usize) -> Option<&T> {
+        if self.width == 0 {
+            return None;
+        }
+
+        self.get(column % self.width, row)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        let Grid {
+            ref cells,
+            width,
+            height,
+        } = *self;
+
+        (0..height)
+            .map(move |row| row * width)
+            .map(move |first_index| &cells[first_index..first_index + width])
+    }
+
+    fn index(&self, column: usize, row: usize) -> usize {
+        column + row * self.width
+    }
+}
+
+impl<T: Copy> Grid<T> {
+    /// Iterate over every cell in row-major order, yielding each cell's
+    /// column, row, and value.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize, T)> + '_ {
+        let width = self.width;
+
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(ix, &cell)| (ix % width, ix / width, cell))
+    }
+
+    /// Count the cells matching a predicate.
+    pub fn count_cells(&self, predicate: impl Fn(T) -> bool) -> usize {
+        self.cells.iter().filter(|&&cell| predicate(cell)).count()
+    }
+
+    /// Iterate over a column's cells from top to bottom. Like
+    /// [`Grid::get()`] this doesn't wrap, so a column past the right edge of
+    /// the grid is empty.
+    pub fn column(&self, column: usize) -> impl Iterator<Item = T> + '_ {
+        let height = if column < self.width { self.height } else { 0 };
+
+        (0..height).map(move |row| self.cells[self.index(column, row)])
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = Vec<T>> + '_ {
+        (0..self.width).map(move |column| self.column(column).collect())
+    }
+
+    /// Iterate over the (up to) eight cells surrounding a location, without
+    /// wrapping around the edges of the grid.
+    pub fn neighbors(
+        &self,
+        column: usize,
+        row: usize,
+    ) -> impl Iterator<Item = (usize, usize, T)> + '_ {
+        const OFFSETS: &[(isize, isize)] = &[
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ];
+
+        self.neighbors_at_offsets(column, row, OFFSETS)
+    }
+
+    /// Iterate over the (up to) four cells directly above, below, left, and
+    /// right of a location, without wrapping around the edges of the grid.
+    pub fn orthogonal_neighbors(
+        &self,
+        column: usize,
+        row: usize,
+    ) -> impl Iterator<Item = (usize, usize, T)> + '_ {
+        const OFFSETS: &[(isize, isize)] = &[(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+        self.neighbors_at_offsets(column, row, OFFSETS)
+    }
+
+    /// Find every location connected to `start` (via orthogonal neighbors,
+    /// without wrapping) whose cell matches the predicate.
+    ///
+    /// Locations are returned in the order they were visited by a
+    /// breadth-first search, and nothing is returned if the `start` cell
+    /// doesn't match.
+    pub fn flood_fill(
+        &self,
+        start: (usize, usize),
+        matches: impl Fn(T) -> bool,
+    ) -> Vec<(usize, usize)> {
+        let (column, row) = start;
+        match self.get(column, row) {
+            Some(&cell) if matches(cell) => {},
+            _ => return Vec::new(),
+        }
+
+        let mut visited = vec![false; self.cells.len()];
+        visited[self.index(column, row)] = true;
+        let mut to_visit = VecDeque::new();
+        to_visit.push_back(start);
+        let mut region = Vec::new();
+
+        while let Some((column, row)) = to_visit.pop_front() {
+            region.push((column, row));
+
+            for (column, row, cell) in self.orthogonal_neighbors(column, row) {
+                let ix = self.index(column, row);
+
+                if !visited[ix] && matches(cell) {
+                    visited[ix] = true;
+                    to_visit.push_back((column, row));
+                }
+            }
+        }
+
+        region
+    }
+
+    fn neighbors_at_offsets(
+        &self,
+        column: usize,
+        row: usize,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize, T)> + '_ {
+        offsets.iter().filter_map(move |&(dx, dy)| {
+            let column = column as isize + dx;
+            let row = row as isize + dy;
+
+            if column < 0 || row < 0 {
+                return None;
+            }
+
+            let (column, row) = (column as usize, row as usize);
+            self.get(column, row).map(|&cell| (column, row, cell))
+        })
+    }
+
+    /// Rotate the grid 90 degrees clockwise.
+    pub fn rotate90(&self) -> Grid<T> {
+        let height = self.height;
+
+        self.rearrange(self.height, self.width, |column, row| {
+            (row, height - 1 - column)
+        })
+    }
+
+    /// Rotate the grid 180 degrees.
+    pub fn rotate180(&self) -> Grid<T> {
+        let (width, height) = (self.width, self.height);
+
+        self.rearrange(self.width, self.height, |column, row| {
+            (width - 1 - column, height - 1 - row)
+        })
+    }
+
+    /// Rotate the grid 270 degrees clockwise (i.e. 90 degrees
+    /// anticlockwise).
+    pub fn rotate270(&self) -> Grid<T> {
+        let width = self.width;
+
+        self.rearrange(self.height, self.width, |column, row| {
+            (width - 1 - row, column)
+        })
+    }
+
+    /// Flip the grid along its main diagonal, so rows become columns.
+    pub fn transpose(&self) -> Grid<T> {
+        self.rearrange(self.height, self.width, |column, row| (row, column))
+    }
+
+    /// Create a new `width` x `height` grid, using `source` to find which
+    /// cell in this grid should go at each `(column, row)` location.
+    fn rearrange<F>(&self, width: usize, height: usize, source: F) -> Grid<T>
+    where
+        F: Fn(usize, usize) -> (usize, usize),
+    {
+        let mut cells = Vec::with_capacity(self.cells.len());
+
+        for row in 0..height {
+            for column in 0..width {
+                let (column, row) = source(column, row);
+                cells.push(self.cells[self.index(column, row)]);
+            }
+        }
+
+        Grid::new(width, height, cells)
+    }
+}
+
+impl<T> FromStr for Grid<T>
+where
+    T: TryFrom<char>,
+    T::Error: Into<Error>,
+{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Grid::try_from(s)
+    }
+}
+
+impl<'input, T> TryFrom<&'input str> for Grid<T>
+where
+    T: TryFrom<char>,
+    T::Error: Into<Error>,
+{
+    type Error = Error;
+
+    fn try_from(s: &'input str) -> Result<Self, Self::Error> {
+        let mut lines = s.lines().filter(|l| !l.is_empty());
+
+        let mut cells = Vec::new();
+
+        // we parse the first line to get the width
+        let first_line = lines.next().context("The grid can't be empty")?;
+        append_cells(&mut cells, first_line)
+            .context("Unable to read line 1")?;
+
+        let width = cells.len();
+        let mut height = 1;
+
+        for line in lines {
+            height += 1;
+            let current_length = cells.len();
+
+            append_cells(&mut cells, line)
+                .with_context(|| format!("Unable to read line {}", height))?;
+
+            let items_added = cells.len() - current_length;
+            if items_added != width {
+                anyhow::bail!(
+                    "The grid should be {} items wide but line {} had {} items",
+                    width,
+                    height,
+                    items_added
+                );
+            }
+        }
+
+        Ok(Grid {
+            cells,
+            width,
+            height,
+        })
+    }
+}
+
+/// A [`Grid`] where each cell is a single digit from `0` to `9` (e.g. a height
+/// map).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DigitGrid(pub Grid<u8>);
+
+impl FromStr for DigitGrid {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DigitGrid::try_from(s)
+    }
+}
+
+impl<'input> TryFrom<&'input str> for DigitGrid {
+    type Error = Error;
+
+    fn try_from(s: &'input str) -> Result<Self, Self::Error> {
+        let Grid {
+            cells,
+            width,
+            height,
+        } = Grid::<Digit>::try_from(s)?;
+        let cells = cells.into_iter().map(|Digit(d)| d).collect();
+
+        Ok(DigitGrid(Grid::new(width, height, cells)))
+    }
+}
+
+impl Deref for DigitGrid {
+    type Target = Grid<u8>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+struct Digit(u8);
+
+impl TryFrom<char> for Digit {
+    type Error = Error;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c.to_digit(10) {
+            Some(digit) => Ok(Digit(digit as u8)),
+            None => anyhow::bail!("Expected a digit, found \"{}\"", c),
+        }
+    }
+}
+
+fn append_cells<T>(dest: &mut Vec<T>, line: &str) -> Result<(), Error>
+where
+    T: TryFrom<char>,
+    T::Error: Into<Error>,
+{
+    for letter in line.trim().chars() {
+        let cell = T::try_from(letter).map_err(Into::into)?;
+        dest.push(cell);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    enum Cell {
+        Open,
+        Wall,
+    }
+
+    impl TryFrom<char> for Cell {
+        type Error = Error;
+
+        fn try_from(c: char) -> Result<Self, Self::Error> {
+            match c {
+                '.' => Ok(Cell::Open),
+                '#' => Ok(Cell::Wall),
+                other => anyhow::bail!("Unknown cell, \"{}\"", other),
+            }
+        }
+    }
+
+    #[test]
+    fn line_errors_mention_the_line_number() {
+        let src = "1\n2\nthree\n4\n";
+
+        let err = Lines::<u32>::from_str(src).unwrap_err();
+
+        assert_eq!(err.to_string(), "Unable to parse line 3: \"three\"");
+    }
+
+    #[test]
+    fn skip_commented_lines() {
+        let src = "# expenses\n1721\n979\n\n  # this one is important\n366\n// not a comment\n";
+
+        let err = Lines::<u32>::parse_ignoring_comments(src, "#").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unable to parse line 7: \"// not a comment\""
+        );
+
+        let src = src.replace("// not a comment\n", "");
+        let got = Lines::<u32>::parse_ignoring_comments(&src, "#").unwrap();
+        assert_eq!(got, Lines(vec![1721, 979, 366]));
+
+        assert!(Lines::<u32>::from_str(&src).is_err());
+    }
+
+    #[test]
+    fn collect_into_lines() {
+        let lines: Lines<u32> = (1..=3).collect();
+
+        assert_eq!(lines, Lines(vec![1, 2, 3]));
+        assert_eq!(lines.iter().sum::<u32>(), 6);
+    }
+
+    #[test]
+    fn parse_lines_as_they_are_read() {
+        let src = ["1721", "", "979\r", "three", "366"];
+        let mut lines_read = 0;
+        let lines = src.iter().map(|line| {
+            lines_read += 1;
+            Ok::<_, Error>(line.to_string())
+        });
+
+        let err = Lines::<u32>::from_lines(lines).unwrap_err();
+
+        assert_eq!(err.to_string(), "Unable to parse line 4: \"three\"");
+        assert_eq!(lines_read, 4);
+
+        let lines =
+            src[..3].iter().map(|line| Ok::<_, Error>(line.to_string()));
+        assert_eq!(Lines::from_lines(lines).unwrap(), Lines(vec![1721, 979]));
+    }
+
+    #[test]
+    fn streamed_and_buffered_lines_skip_the_same_lines() {
+        let inputs = ["1\n\n2\r\n3\n", "1\n   \n2\n", "\n\n"];
+
+        for src in inputs {
+            let buffered = Lines::<u32>::from_str(src);
+            let streamed = Lines::<u32>::from_lines(
+                src.lines().map(|line| Ok::<_, Error>(line.to_string())),
+            );
+
+            match (buffered, streamed) {
+                (Ok(buffered), Ok(streamed)) => {
+                    assert_eq!(buffered, streamed, "{:?}", src)
+                },
+                (Err(buffered), Err(streamed)) => assert_eq!(
+                    buffered.to_string(),
+                    streamed.to_string(),
+                    "{:?}",
+                    src
+                ),
+                (buffered, streamed) => {
+                    panic!("{:?}: {:?} vs {:?}", src, buffered, streamed)
+                },
+            }
         }
     }
+
+    #[test]
+    fn build_lines_incrementally() {
+        let mut lines = Lines::<u32>::default();
+
+        lines.push(1);
+        lines.extend(vec![2, 3]);
+        lines.push(4);
+
+        assert_eq!(lines, Lines(vec![1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn iterate_over_borrowed_lines() {
+        let lines: Lines<u32> = "1\n2\n3".parse().unwrap();
+        let mut total = 0;
+
+        for number in &lines {
+            total += number;
+        }
+        for number in &lines {
+            total += number;
+        }
+
+        assert_eq!(total, 12);
+        assert_eq!(lines.len(), 3);
+    }
+
+    #[test]
+    fn raw_lines_keep_blank_lines() {
+        let src = "a\n\nb\n";
+
+        let lines: Lines<String> = src.parse().unwrap();
+        let raw: RawLines<String> = src.parse().unwrap();
+
+        assert_eq!(*lines, &["a", "b"]);
+        assert_eq!(*raw, &["a", "", "b"]);
+        // blank lines are still given to the parser
+        assert!(RawLines::<u32>::from_str("1\n\n2").is_err());
+    }
+
+    #[test]
+    fn comma_separated_ignores_trailing_newlines() {
+        let got: CommaSeparated<u32> = "3,4,3,1,2\n".parse().unwrap();
+
+        assert_eq!(*got, &[3, 4, 3, 1, 2]);
+    }
+
+    #[test]
+    fn comma_separated_skips_empty_fields() {
+        let got: CommaSeparated<u32> = "0, 3,,6,\n".parse().unwrap();
+
+        assert_eq!(*got, &[0, 3, 6]);
+    }
+
+    #[test]
+    fn comma_separated_propagates_parse_errors() {
+        assert!(CommaSeparated::<u32>::from_str("1,x,3").is_err());
+    }
+
+    #[test]
+    fn numbers_can_be_separated_by_any_whitespace() {
+        let src = " 1 2\t3\n4\r\n\n  5\t\t6 \n";
+
+        let got: Numbers<u32> = src.parse().unwrap();
+
+        assert_eq!(got.0, &[1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn number_errors_mention_the_item() {
+        let err = Numbers::<u32>::from_str("1 2\nx").unwrap_err();
+
+        assert_eq!(err.to_string(), "Unable to parse item 3: \"x\"");
+    }
+
+    #[test]
+    fn groups_skip_leading_and_repeated_blank_lines() {
+        let src = "\n\nab\nac\n\n\n\nb\nc d\n\n";
+
+        let got: Vec<_> = GroupedLines::from(src).collect();
+
+        assert_eq!(got.len(), 2);
+        assert_eq!(got[0].lines(), &["ab", "ac"]);
+        assert_eq!(got[0].joined(), "ab\nac");
+        assert_eq!(got[1].lines(), &["b", "c d"]);
+        assert_eq!(got[1].as_str_with_spaces(), "b c d");
+    }
+
+    #[test]
+    fn count_groups_without_consuming() {
+        let src = "abc\n\na\nb\nc\n\nab\nac\n\na\na\na\na\n\nb";
+
+        assert_eq!(count_groups(src), 5);
+
+        let mut groups = GroupedLines::from(src);
+        groups.next();
+        assert_eq!(groups.clone().count(), 4);
+        assert_eq!(groups.next().unwrap().lines(), &["a", "b", "c"]);
+    }
+
+    #[test]
+    fn groups_with_windows_line_endings() {
+        let unix: Vec<_> = GroupedLines::from("ab\nac\n\nb\nc d\n").collect();
+
+        let windows: Vec<_> =
+            GroupedLines::from("ab\r\nac\r\n\r\nb\r\nc d\r\n").collect();
+        let stray_carriage_returns: Vec<_> =
+            GroupedLines::from("ab\r\nac\r\r\n\r\r\nb\nc d\r").collect();
+
+        assert_eq!(unix.len(), 2);
+        assert_eq!(windows, unix);
+        assert_eq!(stray_carriage_returns, unix);
+    }
+
+    #[test]
+    fn paragraphs_are_slices_of_the_input() {
+        let src = "\nab\nac\n\n\nb\n\n\n";
+
+        let got = Paragraphs::from(src);
+
+        assert_eq!(got.0, &["ab\nac", "b"]);
+    }
+
+    #[test]
+    fn paragraphs_with_windows_line_endings() {
+        let src = "ab\r\nac\r\n\r\nb\r\nc d\r\n\r\n";
+
+        let got = Paragraphs::from(src);
+
+        assert_eq!(got.0, &["ab\r\nac", "b\r\nc d"]);
+    }
+
+    #[test]
+    fn parse_a_grid() {
+        let src = "\n#..\n.#.\n";
+
+        let got: Grid<Cell> = src.parse().unwrap();
+
+        assert_eq!(got.width(), 3);
+        assert_eq!(got.height(), 2);
+        assert_eq!(got.get(0, 0), Some(&Cell::Wall));
+        assert_eq!(got.get(1, 1), Some(&Cell::Wall));
+        assert_eq!(got.get(3, 1), None);
+        assert_eq!(got.get_wrapping(4, 1), Some(&Cell::Wall));
+        assert_eq!(
+            got.rows().nth(1).unwrap(),
+            &[Cell::Open, Cell::Wall, Cell::Open]
+        );
+    }
+
+    #[test]
+    fn neighbors_dont_wrap() {
+        let (w, o) = (Cell::Wall, Cell::Open);
+        let grid: Grid<Cell> = "#.#\n..#\n#..".parse().unwrap();
+
+        let corner: Vec<_> = grid.neighbors(0, 0).collect();
+        assert_eq!(corner, &[(1, 0, o), (0, 1, o), (1, 1, o)]);
+
+        let centre: Vec<_> = grid.neighbors(1, 1).collect();
+        assert_eq!(centre.len(), 8);
+        assert_eq!(centre.iter().filter(|n| n.2 == w).count(), 4);
+
+        let edge: Vec<_> = grid.orthogonal_neighbors(2, 1).collect();
+        assert_eq!(edge, &[(2, 0, w), (1, 1, o), (2, 2, o)]);
+
+        assert_eq!(grid.orthogonal_neighbors(1, 1).count(), 4);
+        assert_eq!(grid.neighbors(5, 5).count(), 0);
+    }
+
+    #[test]
+    fn rotate_a_rectangular_grid() {
+        let grid: Grid<Cell> = "#..\n..#".parse().unwrap();
+        let parse = |s: &str| Grid::<Cell>::from_str(s).unwrap();
+
+        let got = grid.rotate90();
+
+        assert_eq!((got.width(), got.height()), (2, 3));
+        assert_eq!(got, parse(".#\n..\n#."));
+        assert_eq!(grid.rotate180(), parse("#..\n..#"));
+        assert_eq!(grid.rotate270(), parse(".#\n..\n#."));
+        assert_eq!(grid.transpose(), parse("#.\n..\n.#"));
+    }
+
+    #[test]
+    fn rotating_four_times_gives_the_original_grid() {
+        let grid: Grid<Cell> =
+            "..##.......\n#...#...#..\n.#....#..#.\n".parse().unwrap();
+
+        let rotated = grid.rotate90().rotate90().rotate90().rotate90();
+
+        assert_eq!(rotated, grid);
+        assert_eq!(grid.rotate90().rotate90(), grid.rotate180());
+        assert_eq!(grid.rotate90().rotate180(), grid.rotate270());
+        assert_eq!(grid.transpose().transpose(), grid);
+    }
+
+    #[test]
+    fn positions_are_row_major() {
+        let grid: Grid<Cell> = "#.\n.#\n..".parse().unwrap();
+
+        let got: Vec<_> = grid.positions().collect();
+
+        assert_eq!(got.len(), 6);
+        assert_eq!(got[0], (0, 0, Cell::Wall));
+        assert_eq!(got[1], (1, 0, Cell::Open));
+        assert_eq!(got[3], (1, 1, Cell::Wall));
+        assert_eq!(got[5], (1, 2, Cell::Open));
+        assert!(got
+            .iter()
+            .all(|&(column, row, cell)| grid.get(column, row) == Some(&cell)));
+        assert_eq!(grid.count_cells(|cell| cell == Cell::Wall), 2);
+        assert_eq!(grid.count_cells(|cell| cell == Cell::Open), 4);
+    }
+
+    #[test]
+    fn build_a_grid_from_rows() {
+        let (w, o) = (Cell::Wall, Cell::Open);
+
+        let got = Grid::from_rows(vec![vec![w, o, o], vec![o, o, w]]).unwrap();
+
+        assert_eq!(got, Grid::from_str("#..\n..#").unwrap());
+        let err =
+            Grid::from_rows(vec![vec![w, o], vec![o], vec![w, w]]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The grid should be 2 items wide but row 2 had 1 items"
+        );
+        assert!(Grid::<Cell>::from_rows(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn flood_fill_a_single_region() {
+        let grid: Grid<Cell> = "..#..\n.##..\n#..#.\n..#..".parse().unwrap();
+        let is_open = |cell| cell == Cell::Open;
+
+        let mut got = grid.flood_fill((0, 0), is_open);
+        got.sort();
+
+        assert_eq!(got, &[(0, 0), (0, 1), (1, 0)]);
+        let other_region = grid.flood_fill((4, 0), is_open);
+        assert_eq!(other_region.len(), 7);
+        assert_eq!(other_region[0], (4, 0));
+        assert!(!other_region.contains(&(0, 0)));
+        assert!(grid.flood_fill((2, 0), is_open).is_empty());
+        assert!(grid.flood_fill((5, 0), is_open).is_empty());
+    }
+
+    #[test]
+    fn iterate_over_columns() {
+        let grid: Grid<Cell> = "#.\n.#\n#.".parse().unwrap();
+
+        let first_column: Vec<_> = grid.column(0).collect();
+
+        assert_eq!(first_column, &[Cell::Wall, Cell::Open, Cell::Wall]);
+        assert_eq!(grid.column(2).count(), 0);
+        assert_eq!(grid.columns().count(), 2);
+        assert_eq!(
+            grid.columns().collect::<Vec<_>>(),
+            grid.transpose()
+                .rows()
+                .map(<[Cell]>::to_vec)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn parse_a_digit_grid() {
+        let src = "2199943210\n3987894921\n9856789892\n";
+
+        let got: DigitGrid = src.parse().unwrap();
+
+        assert_eq!(got.width(), 10);
+        assert_eq!(got.height(), 3);
+        assert_eq!(got.get(0, 0), Some(&2));
+        assert_eq!(got.get(9, 2), Some(&2));
+        assert_eq!(got.get(10, 0), None);
+        assert_eq!(got.rows().nth(1).unwrap(), &[3, 9, 8, 7, 8, 9, 4, 9, 2, 1]);
+    }
+
+    #[test]
+    fn digit_grids_must_be_rectangular() {
+        let src = "123\n456\n78\n";
+
+        let err = DigitGrid::from_str(src).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "The grid should be 3 items wide but line 3 had 2 items"
+        );
+    }
+
+    #[test]
+    fn digit_grids_only_contain_digits() {
+        let err = DigitGrid::from_str("123\n4x6").unwrap_err();
+
+        assert_eq!(
+            format!("{:#}", err),
+            "Unable to read line 2: Expected a digit, found \"x\""
+        );
+    }
+
+    #[test]
+    fn grids_must_be_rectangular() {
+        let src = "#..\n.#.\n..\n";
+
+        let err = Grid::<Cell>::from_str(src).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "The grid should be 3 items wide but line 3 had 2 items"
+        );
+    }
 }
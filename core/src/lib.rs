@@ -1,5 +1,24 @@
+mod answer;
 mod challenge;
 mod inputs;
+mod solver;
 
-pub use challenge::{all_challenges, Challenge, Example};
-pub use inputs::{GroupedLines, Lines};
+pub use answer::Answer;
+pub use challenge::{
+    all_challenges, all_challenges_sorted, parse_challenge_header,
+    parse_number, validate_unique_challenges, Challenge, Example, SolveLines,
+    SolveWithProgress, CHALLENGE_NUMBERS, DEFAULT_YEAR,
+};
+pub use inputs::{
+    count_groups, CommaSeparated, DigitGrid, Grid, Group, GroupedLines, Lines,
+    Numbers, Paragraphs, RawLines,
+};
+pub use solver::Solver;
+
+/// Implementation details used by [`register_solver!()`].
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::solver::{debug, solve, solve_bytes, validate};
+    pub use inventory;
+    pub use linkme;
+}
@@ -0,0 +1,125 @@
+use crate::{Example, DEFAULT_YEAR};
+use anyhow::Error;
+use std::{fmt::Debug, str::FromStr};
+
+/// An alternative to the `#[aoc_macros::challenge]` attribute where a
+/// challenge's metadata is provided explicitly instead of being parsed from
+/// doc-comments.
+///
+/// Types implementing `Solver` are registered with [`register_solver!()`].
+///
+/// ```rust
+/// use aoc_core::{Lines, Solver};
+///
+/// struct ReportRepair;
+///
+/// impl Solver for ReportRepair {
+///     const NUMBER: &'static str = "1a";
+///     const NAME: &'static str = "Report Repair";
+///     type Input = Lines<u32>;
+///
+///     fn solve(input: Lines<u32>) -> Result<String, anyhow::Error> {
+///         Ok(input.iter().sum::<u32>().to_string())
+///     }
+/// }
+///
+/// aoc_core::register_solver!(ReportRepair);
+/// ```
+pub trait Solver {
+    const NUMBER: &'static str;
+    const NAME: &'static str;
+    const YEAR: u16 = DEFAULT_YEAR;
+    const DESCRIPTION: &'static str = "";
+    const EXAMPLES: &'static [Example] = &[];
+    const TAGS: &'static [&'static str] = &[];
+    const STARS: u8 = 0;
+    const LINE_ORIENTED: bool = false;
+
+    type Input: FromStr + Debug;
+
+    fn solve(input: Self::Input) -> Result<String, Error>;
+
+    /// Guess whether an input is for this challenge.
+    fn detect(_input: &str) -> bool {
+        false
+    }
+}
+
+/// Register a [`Solver`] so it is included in [`crate::all_challenges()`].
+#[macro_export]
+macro_rules! register_solver {
+    ($solver:ty) => {
+        $crate::__private::inventory::submit! {
+            #![crate = $crate::__private]
+            $crate::Challenge {
+                year: <$solver as $crate::Solver>::YEAR,
+                number: <$solver as $crate::Solver>::NUMBER,
+                name: <$solver as $crate::Solver>::NAME,
+                description: <$solver as $crate::Solver>::DESCRIPTION,
+                examples: <$solver as $crate::Solver>::EXAMPLES,
+                tags: <$solver as $crate::Solver>::TAGS,
+                stars: <$solver as $crate::Solver>::STARS,
+                line_oriented: <$solver as $crate::Solver>::LINE_ORIENTED,
+                accepts_bytes: false,
+                detect: <$solver as $crate::Solver>::detect,
+                solve: $crate::__private::solve::<$solver>,
+                solve_bytes: $crate::__private::solve_bytes::<$solver>,
+                validate: $crate::__private::validate::<$solver>,
+                debug: $crate::__private::debug::<$solver>,
+                solve_with_progress: None,
+                solve_lines: None,
+            }
+        }
+
+        const _: () = {
+            #[$crate::__private::linkme::distributed_slice(
+                $crate::CHALLENGE_NUMBERS
+            )]
+            #[linkme(crate = $crate::__private::linkme)]
+            static ENTRY: (u16, &str) = (
+                <$solver as $crate::Solver>::YEAR,
+                <$solver as $crate::Solver>::NUMBER,
+            );
+        };
+    };
+}
+
+fn parse<S>(input: &str) -> Result<S::Input, Error>
+where
+    S: Solver,
+    <S::Input as FromStr>::Err: Into<Error>,
+{
+    input.parse().map_err(Into::into)
+}
+
+pub fn solve<S>(input: &str) -> Result<String, Error>
+where
+    S: Solver,
+    <S::Input as FromStr>::Err: Into<Error>,
+{
+    S::solve(parse::<S>(input)?)
+}
+
+pub fn solve_bytes<S>(input: &[u8]) -> Result<String, Error>
+where
+    S: Solver,
+    <S::Input as FromStr>::Err: Into<Error>,
+{
+    solve::<S>(std::str::from_utf8(input)?)
+}
+
+pub fn validate<S>(input: &str) -> Result<(), Error>
+where
+    S: Solver,
+    <S::Input as FromStr>::Err: Into<Error>,
+{
+    parse::<S>(input).map(|_| ())
+}
+
+pub fn debug<S>(input: &str) -> Result<String, Error>
+where
+    S: Solver,
+    <S::Input as FromStr>::Err: Into<Error>,
+{
+    parse::<S>(input).map(|input| format!("{:#?}", input))
+}
@@ -0,0 +1,39 @@
+use anyhow::Error;
+use aoc_core::{Example, Lines, Solver};
+
+struct SumOfNumbers;
+
+impl Solver for SumOfNumbers {
+    const NUMBER: &'static str = "1";
+    const NAME: &'static str = "Sum Of Numbers";
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: "1\n2\n3",
+        expected: "6",
+    }];
+    const TAGS: &'static [&'static str] = &["math"];
+
+    type Input = Lines<u32>;
+
+    fn solve(input: Lines<u32>) -> Result<String, Error> {
+        Ok(input.iter().sum::<u32>().to_string())
+    }
+}
+
+aoc_core::register_solver!(SumOfNumbers);
+
+#[test]
+fn solvers_are_registered_as_challenges() {
+    let challenge = aoc_core::all_challenges()
+        .find(|c| c.number == "1")
+        .unwrap();
+
+    assert_eq!(challenge.name, "Sum Of Numbers");
+    assert_eq!(challenge.tags, &["math"]);
+    assert!(!challenge.line_oriented);
+
+    for example in challenge.examples {
+        assert_eq!(challenge.run(example.input).unwrap(), example.expected);
+    }
+    assert!((challenge.validate)("1\nx").is_err());
+    assert!(challenge.debug_input("42").unwrap().contains("42"));
+}
@@ -1,22 +1,81 @@
 use once_cell::sync::Lazy;
 use proc_macro::TokenStream;
 use proc_macro2::Span;
-use quote::{quote, ToTokens};
+use pulldown_cmark::{Event, Parser, Tag};
+use quote::{quote, quote_spanned, ToTokens};
 use regex::Regex;
-use syn::{Error, Ident, ItemFn, Lit, Meta, MetaNameValue};
+use syn::{
+    parse::Parser as _, punctuated::Punctuated, spanned::Spanned, Error, FnArg,
+    Ident, ItemFn, Lit, Meta, MetaList, MetaNameValue, NestedMeta, PatType,
+    Path, Token, Type,
+};
 
+/// Register a function as an Advent of Code challenge.
+///
+/// The challenge's number and name come from a `Day 4a: Passport Processing`
+/// line in the doc-comment, with the description and examples taken from the
+/// `# Description` and `# Examples` sections.
+///
+/// The function must take exactly one argument, the parsed input, and its type
+/// is also used to generate the challenge's `validate` and `debug` functions.
+/// This means the argument type must implement [`Debug`](std::fmt::Debug).
+///
+/// The attribute also accepts a couple of optional arguments:
+///
+/// - `example(input = "...", expected = "...")` adds an example, and may be
+///   repeated
+/// - `tags("grid", "parsing")` attaches keywords that can be used to group
+///   similar challenges
+/// - `line_oriented` marks a challenge which takes a `Lines<T>`, letting the
+///   CLI parse each line as it is read instead of loading the whole input
+/// - `bytes` parses the puzzle input directly from a `&[u8]` using
+///   `TryFrom<&[u8]>`, avoiding UTF-8 validation
+/// - `parse_with = "TryFrom"` (the default) converts the puzzle input to the
+///   function's argument type using `TryFrom<&str>`, while
+///   `parse_with = "FromStr"` uses `str::parse()` instead
+/// - `detect = "looks_like_input"` names a `fn(&str) -> bool` which guesses
+///   whether an input is for this challenge, letting the CLI pick the right
+///   challenge automatically. Without it the challenge is never detected
+/// - `progress = "part_2_with_progress"` names an alternative to the
+///   challenge function which also accepts a `&mut dyn FnMut(f32)`, used to
+///   report how far through solving it is
+/// - `stars = 2` records how many stars have been earned for the challenge,
+///   from 0 (the default) to 2
+/// - `year = 2021` sets which Advent of Code event the challenge is from,
+///   defaulting to `aoc_core::DEFAULT_YEAR`
+///
+/// Because the generated code passes the input directly to the function, the
+/// argument type is allowed to borrow from the input string (e.g.
+/// `GroupedLines<'_>`). These types must use `TryFrom<&'a str>` because
+/// `FromStr` has no way to tie its output to the input's lifetime. The return
+/// value is converted to a `String` (using `aoc_core::Answer`, which is
+/// implemented for anything that implements `Display`) before the input goes
+/// out of scope, so it may borrow from the input too.
+///
+/// Each challenge also defines a hidden constant named after its year and
+/// number, so accidentally using the same `Day 4a` header twice in a module
+/// fails to compile.
 #[proc_macro_attribute]
-pub fn challenge(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn challenge(attr: TokenStream, item: TokenStream) -> TokenStream {
     let function = syn::parse_macro_input!(item as ItemFn);
 
-    let info = match parse_challenge(&function) {
+    let attributes = match parse_attributes(attr.into()) {
+        Ok(a) => a,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let info = match parse_challenge(&function, attributes) {
         Ok(i) => i,
         Err(e) => return e.to_compile_error().into(),
     };
 
+    let registry_entry = info.registry_entry();
+
     quote! (
         #function
 
+        #registry_entry
+
         inventory::submit! {
             #info
         }
@@ -24,8 +83,179 @@ pub fn challenge(_attr: TokenStream, item: TokenStream) -> TokenStream {
     .into()
 }
 
-fn parse_challenge(function: &ItemFn) -> Result<ChallengeInfo, Error> {
+/// Arguments passed to the `#[challenge(...)]` attribute itself.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Attributes {
+    examples: Vec<(String, String)>,
+    tags: Vec<String>,
+    line_oriented: bool,
+    bytes: bool,
+    parse_with: ParseWith,
+    year: Option<u16>,
+    stars: u8,
+    detect: Option<Path>,
+    progress: Option<Path>,
+}
+
+/// How the puzzle input should be converted into the challenge's argument.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+enum ParseWith {
+    #[default]
+    TryFrom,
+    FromStr,
+}
+
+/// Parse the arguments to `#[challenge(...)]`.
+///
+/// Examples can be provided with
+/// `#[challenge(example(input = "...", expected = "..."))]`, and the `example`
+/// may be repeated as many times as necessary.
+fn parse_attributes(
+    tokens: proc_macro2::TokenStream,
+) -> Result<Attributes, Error> {
+    let args =
+        Punctuated::<NestedMeta, Token![,]>::parse_terminated.parse2(tokens)?;
+    let mut attributes = Attributes::default();
+
+    for arg in &args {
+        match arg {
+            NestedMeta::Meta(Meta::List(list))
+                if list.path.is_ident("example") =>
+            {
+                attributes.examples.push(parse_example_attribute(list)?);
+            },
+            NestedMeta::Meta(Meta::List(list))
+                if list.path.is_ident("tags") =>
+            {
+                for nested in &list.nested {
+                    match nested {
+                        NestedMeta::Lit(Lit::Str(s)) => {
+                            attributes.tags.push(s.value())
+                        },
+                        other => {
+                            return Err(Error::new_spanned(
+                                other,
+                                "Tags should be string literals",
+                            ))
+                        },
+                    }
+                }
+            },
+            NestedMeta::Meta(Meta::Path(path))
+                if path.is_ident("line_oriented") =>
+            {
+                attributes.line_oriented = true;
+            },
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("bytes") => {
+                attributes.bytes = true;
+            },
+            NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                path,
+                lit: Lit::Str(s),
+                ..
+            })) if path.is_ident("parse_with") => {
+                attributes.parse_with = match s.value().as_str() {
+                    "TryFrom" => ParseWith::TryFrom,
+                    "FromStr" => ParseWith::FromStr,
+                    _ => {
+                        return Err(Error::new_spanned(
+                            s,
+                            r#"Expected either "TryFrom" or "FromStr""#,
+                        ))
+                    },
+                };
+            },
+            NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                path,
+                lit: Lit::Int(year),
+                ..
+            })) if path.is_ident("year") => {
+                attributes.year = Some(year.base10_parse()?);
+            },
+            NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                path,
+                lit: Lit::Str(s),
+                ..
+            })) if path.is_ident("detect") => {
+                attributes.detect = Some(s.parse()?);
+            },
+            NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                path,
+                lit: Lit::Str(s),
+                ..
+            })) if path.is_ident("progress") => {
+                attributes.progress = Some(s.parse()?);
+            },
+            NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                path,
+                lit: Lit::Int(stars),
+                ..
+            })) if path.is_ident("stars") => {
+                attributes.stars = stars.base10_parse()?;
+
+                if attributes.stars > 2 {
+                    return Err(Error::new_spanned(
+                        stars,
+                        "A challenge can have at most 2 stars",
+                    ));
+                }
+            },
+            other => {
+                return Err(Error::new_spanned(
+                    other,
+                    r#"Expected something like `example(input = "...", expected = "...")` or `parse_with = "FromStr"`"#,
+                ))
+            },
+        }
+    }
+
+    Ok(attributes)
+}
+
+fn parse_example_attribute(list: &MetaList) -> Result<(String, String), Error> {
+    let mut input = None;
+    let mut expected = None;
+
+    for nested in &list.nested {
+        match nested {
+            NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                path,
+                lit: Lit::Str(s),
+                ..
+            })) if path.is_ident("input") => input = Some(s.value()),
+            NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                path,
+                lit: Lit::Str(s),
+                ..
+            })) if path.is_ident("expected") => expected = Some(s.value()),
+            other => {
+                return Err(Error::new_spanned(
+                    other,
+                    r#"Only `input = "..."` and `expected = "..."` are allowed in an example"#,
+                ))
+            },
+        }
+    }
+
+    match (input, expected) {
+        (Some(input), Some(expected)) => Ok((input, expected)),
+        (None, _) => Err(Error::new_spanned(
+            list,
+            r#"The example is missing its `input = "..."`"#,
+        )),
+        (_, None) => Err(Error::new_spanned(
+            list,
+            r#"The example is missing its `expected = "..."`"#,
+        )),
+    }
+}
+
+fn parse_challenge(
+    function: &ItemFn,
+    attributes: Attributes,
+) -> Result<ChallengeInfo, Error> {
     let function_name = function.sig.ident.clone();
+    let input_type = parse_input_type(function)?;
 
     let doc_attr = function
         .attrs
@@ -38,25 +268,85 @@ fn parse_challenge(function: &ItemFn) -> Result<ChallengeInfo, Error> {
             })) if path.is_ident("doc") => Some(s.value()),
             _ => None,
         })
+        .map(|line| match line.strip_prefix(' ') {
+            Some(stripped) => stripped.to_string(),
+            None => line,
+        })
         .collect::<Vec<_>>()
         .join("\n");
 
-    let (day, name, description) = parse_doc_comment(&doc_attr)?;
+    let (number, name, description) = parse_doc_comment(&doc_attr)?;
+    let mut examples = parse_examples(&doc_attr);
+    examples.extend(attributes.examples);
 
     Ok(ChallengeInfo {
-        number: day.to_string(),
-        name: name.to_string(),
-        description: description.to_string(),
-        examples: Vec::new(),
+        number,
+        name,
+        description,
+        examples,
+        tags: attributes.tags,
+        line_oriented: attributes.line_oriented,
+        bytes: attributes.bytes,
         function_name,
+        input_type,
+        parse_with: attributes.parse_with,
+        year: attributes.year,
+        stars: attributes.stars,
+        detect: attributes.detect,
+        progress: attributes.progress,
     })
 }
 
-fn parse_doc_comment(docs: &str) -> Result<(&str, &str, &str), Error> {
+/// Get the type of the challenge function's only argument.
+fn parse_input_type(function: &ItemFn) -> Result<Type, Error> {
+    let inputs = &function.sig.inputs;
+
+    match inputs.first() {
+        Some(FnArg::Typed(PatType { ty, .. })) if inputs.len() == 1 => {
+            Ok((**ty).clone())
+        },
+        _ => Err(Error::new_spanned(
+            &function.sig,
+            "Challenges should accept exactly one argument, the parsed input",
+        )),
+    }
+}
+
+/// Find all the `example` code blocks in a doc-comment, returning their
+/// `(input, expected)` pairs.
+///
+/// Examples are expected to look something like this:
+///
+/// ````text
+/// ```example
+/// input:
+/// 1721
+/// 979
+/// expected:
+/// 514579
+/// ```
+/// ````
+fn parse_examples(docs: &str) -> Vec<(String, String)> {
     static PATTERN: Lazy<Regex> = Lazy::new(|| {
-        Regex::new(r"(?im)day ([\d\w]+)\s*:\s*([^\n]+)$").unwrap()
+        Regex::new(
+            r"(?ms)^```example\s*?\n\s*input:\s*?\n(.*?)\n\s*expected:\s*?\n(.*?)\n```",
+        )
+        .unwrap()
     });
 
+    PATTERN
+        .captures_iter(docs)
+        .map(|captures| {
+            let input = captures[1].to_string();
+            let expected = captures[2].trim().to_string();
+            (input, expected)
+        })
+        .collect()
+}
+
+/// Parse the challenge number (e.g. `"4a"`), name, and description from a
+/// challenge's doc-comment.
+fn parse_doc_comment(docs: &str) -> Result<(String, String, String), Error> {
     if docs.is_empty() {
         return Err(Error::new(
             Span::call_site(),
@@ -64,32 +354,108 @@ fn parse_doc_comment(docs: &str) -> Result<(&str, &str, &str), Error> {
         ));
     }
 
-    let captures = match PATTERN.captures(docs) {
-        Some(c) => c,
-        None => {
-            return Err(Error::new(
-                Span::call_site(),
-                r#"Unable to determine the challenge name and day. Expected something like "Day 1: Report Repair""#,
-            ))
-        }
+    let (day, name, part) = aoc_core::parse_challenge_header(docs)
+        .map_err(|msg| Error::new(Span::call_site(), msg))?;
+    let number = match part {
+        Some(part) => format!("{}{}", day, part),
+        None => day,
     };
 
-    let day = captures.get(1).unwrap().as_str();
-    let name = captures.get(2).unwrap().as_str();
+    let description = parse_description(docs);
+
+    Ok((number, name, description))
+}
 
-    // TODO: Use pulldown-cmark to extract the description section
-    let description = "";
+/// Extract the text under the `# Description` heading, stopping at the next
+/// heading.
+///
+/// Paragraphs are separated by a blank line and code blocks are copied
+/// verbatim. If there is no description section, an empty string is returned.
+fn parse_description(docs: &str) -> String {
+    let mut in_heading = false;
+    let mut in_description = false;
+    let mut heading = String::new();
+    let mut current = String::new();
+    let mut blocks = Vec::new();
 
-    Ok((day, name, description))
+    for event in Parser::new(docs) {
+        match event {
+            Event::Start(Tag::Heading(_)) => {
+                if in_description {
+                    break;
+                }
+
+                in_heading = true;
+                heading.clear();
+            },
+            Event::End(Tag::Heading(_)) => {
+                in_heading = false;
+                in_description =
+                    heading.trim().eq_ignore_ascii_case("description");
+            },
+            Event::Text(text) | Event::Code(text) => {
+                if in_heading {
+                    heading.push_str(&text);
+                } else if in_description {
+                    current.push_str(&text);
+                }
+            },
+            Event::SoftBreak if in_description => current.push(' '),
+            Event::HardBreak if in_description => current.push('\n'),
+            Event::End(Tag::Paragraph) | Event::End(Tag::CodeBlock(_))
+                if in_description =>
+            {
+                blocks.push(current.trim_end().to_string());
+                current.clear();
+            },
+            _ => {},
+        }
+    }
+
+    blocks.join("\n\n")
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 struct ChallengeInfo {
     number: String,
     name: String,
     description: String,
     examples: Vec<(String, String)>,
+    tags: Vec<String>,
+    line_oriented: bool,
+    bytes: bool,
     function_name: Ident,
+    input_type: Type,
+    parse_with: ParseWith,
+    year: Option<u16>,
+    stars: u8,
+    detect: Option<Path>,
+    progress: Option<Path>,
+}
+
+impl ChallengeInfo {
+    /// Add the challenge's year and number to `aoc_core::CHALLENGE_NUMBERS`.
+    ///
+    /// The static is named after the year and number, so registering the
+    /// same challenge twice in a module is also a compile error.
+    fn registry_entry(&self) -> proc_macro2::TokenStream {
+        let year = self.year.unwrap_or(aoc_core::DEFAULT_YEAR);
+        let name = Ident::new(
+            &format!("__AOC_CHALLENGE_{}_{}", year, self.number.to_uppercase()),
+            self.function_name.span(),
+        );
+
+        let number = &self.number;
+
+        quote! {
+            #[doc(hidden)]
+            #[aoc_core::__private::linkme::distributed_slice(
+                aoc_core::CHALLENGE_NUMBERS
+            )]
+            #[linkme(crate = aoc_core::__private::linkme)]
+            static #name: (u16, &str) = (#year, #number);
+        }
+    }
 }
 
 impl ToTokens for ChallengeInfo {
@@ -99,7 +465,16 @@ impl ToTokens for ChallengeInfo {
             name,
             description,
             examples,
+            tags,
+            line_oriented,
+            bytes,
             function_name,
+            input_type,
+            parse_with,
+            year,
+            stars,
+            detect,
+            progress,
         } = self;
 
         let examples = examples.iter().map(|(ref input, ref expected)| {
@@ -111,23 +486,287 @@ impl ToTokens for ChallengeInfo {
             }
         });
 
+        let parse = match parse_with {
+            ParseWith::TryFrom => quote! {
+                std::convert::TryFrom::try_from(input)?
+            },
+            ParseWith::FromStr => quote! {
+                input.parse()?
+            },
+        };
+
+        // point at the argument if its type doesn't implement Debug
+        let debug_input = quote_spanned! { input_type.span() =>
+            format!("{:#?}", input)
+        };
+
+        let year = match year {
+            Some(year) => quote! { #year },
+            None => quote! { aoc_core::DEFAULT_YEAR },
+        };
+
+        let detect = match detect {
+            Some(detect) => quote! { #detect },
+            None => quote! { |_| false },
+        };
+
+        let solve_with_progress = match progress {
+            Some(progress) => quote! {
+                Some(|input, progress| -> Result<String, anyhow::Error> {
+                    let input = #parse;
+                    let result = #progress(input, progress)?;
+
+                    Ok(aoc_core::Answer::format(&result))
+                })
+            },
+            None => quote! { None },
+        };
+
+        // line oriented challenges can parse each line as it is read
+        let solve_lines = if *line_oriented {
+            quote! {
+                Some(|lines| -> Result<String, anyhow::Error> {
+                    let input: #input_type = aoc_core::Lines::from_lines(lines)?;
+                    let result = #function_name(input)?;
+
+                    Ok(aoc_core::Answer::format(&result))
+                })
+            }
+        } else {
+            quote! { None }
+        };
+
+        let parse_bytes = if *bytes {
+            quote! { std::convert::TryFrom::try_from(input)? }
+        } else {
+            quote! {{
+                let input = std::str::from_utf8(input)?;
+                #parse
+            }}
+        };
+
         let got = quote! {
             aoc_core::Challenge {
+                year: #year,
                 number: #number,
                 name: #name,
                 description: #description,
-                examples: &[ #( #examples => #examples )*],
+                examples: &[ #( #examples ),* ],
+                tags: &[ #( #tags ),* ],
+                stars: #stars,
+                line_oriented: #line_oriented,
+                accepts_bytes: #bytes,
+                detect: #detect,
                 solve: |input| -> Result<String, anyhow::Error> {
-                    use std::convert::TryInto as _;
+                    let input = #parse;
+                    let result = #function_name(input)?;
 
-                    let input = input.try_into()?;
+                    Ok(aoc_core::Answer::format(&result))
+                },
+                solve_bytes: |input| -> Result<String, anyhow::Error> {
+                    let input = #parse_bytes;
                     let result = #function_name(input)?;
 
-                    Ok(result.to_string())
+                    Ok(aoc_core::Answer::format(&result))
                 },
+                validate: |input| -> Result<(), anyhow::Error> {
+                    let _: #input_type = #parse;
+                    Ok(())
+                },
+                debug: |input| -> Result<String, anyhow::Error> {
+                    let input: #input_type = #parse;
+                    Ok(#debug_input)
+                },
+                solve_with_progress: #solve_with_progress,
+                solve_lines: #solve_lines,
             }
         };
 
         tokens.extend(got);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_examples_from_docs() {
+        let docs = "Day 1a: Report Repair\n\n# Examples\n\n```example\ninput:\n1721\n979\n\n366\nexpected:\n514579\n```\n\n```example\ninput:\n1\nexpected:\n2\n```\n";
+
+        let got = parse_examples(docs);
+
+        assert_eq!(
+            got,
+            vec![
+                (String::from("1721\n979\n\n366"), String::from("514579")),
+                (String::from("1"), String::from("2")),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_day_and_part_from_the_header() {
+        let inputs = vec![
+            ("Day 4a: Passport Processing", "4a", "Passport Processing"),
+            ("Day 4b: Passport Processing", "4b", "Passport Processing"),
+            ("Day 25: Combo Breaker", "25", "Combo Breaker"),
+            ("day 12B: Rain Risk", "12b", "Rain Risk"),
+        ];
+
+        for (docs, number, name) in inputs {
+            let (got_number, got_name, _) = parse_doc_comment(docs).unwrap();
+
+            assert_eq!(got_number, number);
+            assert_eq!(got_name, name);
+        }
+    }
+
+    #[test]
+    fn extract_the_description_section() {
+        let docs = "Day 4: Passport Processing\n\n# Description\n\nYou arrive at\nthe airport.\n\n```text\nbyr (Birth Year)\niyr (Issue Year)\n```\n\nCount the `cid`.\n\n# Examples\n\nSomething else.";
+
+        let got = parse_description(docs);
+
+        assert_eq!(
+            got,
+            "You arrive at the airport.\n\nbyr (Birth Year)\niyr (Issue Year)\n\nCount the cid."
+        );
+    }
+
+    #[test]
+    fn missing_description_is_empty() {
+        let docs = "Day 4: Passport Processing\n\nSome text.";
+
+        assert_eq!(parse_description(docs), "");
+    }
+
+    #[test]
+    fn examples_can_be_passed_as_attributes() {
+        let tokens = quote! {
+            example(input = "1\n2", expected = "3"),
+            example(expected = "42", input = "40\n2"),
+        };
+
+        let got = parse_attributes(tokens).unwrap();
+
+        assert_eq!(
+            got.examples,
+            vec![
+                (String::from("1\n2"), String::from("3")),
+                (String::from("40\n2"), String::from("42")),
+            ]
+        );
+    }
+
+    #[test]
+    fn line_oriented_challenges() {
+        assert!(!parse_attributes(quote!()).unwrap().line_oriented);
+        assert!(
+            parse_attributes(quote!(line_oriented))
+                .unwrap()
+                .line_oriented
+        );
+    }
+
+    #[test]
+    fn parse_from_bytes() {
+        assert!(!parse_attributes(quote!()).unwrap().bytes);
+        assert!(parse_attributes(quote!(bytes)).unwrap().bytes);
+    }
+
+    #[test]
+    fn parse_tags() {
+        let got = parse_attributes(quote!(tags("grid", "parsing"))).unwrap();
+
+        assert_eq!(got.tags, &["grid", "parsing"]);
+        assert!(parse_attributes(quote!(tags(grid))).is_err());
+    }
+
+    #[test]
+    fn each_challenge_has_a_registry_entry() {
+        let function: ItemFn = syn::parse_quote! {
+            /// Day 4a: Passport Processing
+            fn part_1(input: &str) -> Result<u32, Error> { todo!() }
+        };
+        let attributes = parse_attributes(quote!(year = 2021)).unwrap();
+
+        let info = parse_challenge(&function, attributes).unwrap();
+        let got = info.registry_entry().to_string();
+
+        assert!(got.contains("static __AOC_CHALLENGE_2021_4A"), "{}", got);
+        assert!(got.contains("(2021u16 , \"4a\")"), "{}", got);
+    }
+
+    #[test]
+    fn parse_the_detection_function() {
+        assert!(parse_attributes(quote!()).unwrap().detect.is_none());
+
+        let got = parse_attributes(quote!(detect = "inputs::looks_like_day_3"))
+            .unwrap()
+            .detect
+            .unwrap();
+        assert_eq!(got, syn::parse_quote!(inputs::looks_like_day_3));
+
+        assert!(parse_attributes(quote!(detect = "not a path")).is_err());
+    }
+
+    #[test]
+    fn parse_the_number_of_stars() {
+        assert_eq!(parse_attributes(quote!()).unwrap().stars, 0);
+        assert_eq!(parse_attributes(quote!(stars = 2)).unwrap().stars, 2);
+        assert!(parse_attributes(quote!(stars = 3)).is_err());
+        assert!(parse_attributes(quote!(stars = -1)).is_err());
+    }
+
+    #[test]
+    fn parse_the_year() {
+        assert_eq!(parse_attributes(quote!()).unwrap().year, None);
+        assert_eq!(
+            parse_attributes(quote!(year = 2021)).unwrap().year,
+            Some(2021)
+        );
+        assert!(parse_attributes(quote!(year = 100000)).is_err());
+        assert!(parse_attributes(quote!(year = "2021")).is_err());
+    }
+
+    #[test]
+    fn choose_how_the_input_is_parsed() {
+        let inputs = vec![
+            (quote!(), ParseWith::TryFrom),
+            (quote!(parse_with = "TryFrom"), ParseWith::TryFrom),
+            (quote!(parse_with = "FromStr"), ParseWith::FromStr),
+        ];
+
+        for (tokens, should_be) in inputs {
+            let got = parse_attributes(tokens).unwrap();
+
+            assert_eq!(got.parse_with, should_be);
+        }
+
+        assert!(parse_attributes(quote!(parse_with = "Serde")).is_err());
+    }
+
+    #[test]
+    fn attribute_examples_need_an_input_and_expected_value() {
+        let inputs = vec![
+            (quote!(example(input = "1")), "expected"),
+            (quote!(example(expected = "1")), "input"),
+            (quote!(example(input = "1", output = "2")), "Only"),
+            (quote!(something_else), "Expected"),
+        ];
+
+        for (tokens, should_mention) in inputs {
+            let err = parse_attributes(tokens).unwrap_err();
+
+            assert!(err.to_string().contains(should_mention), "{}", err);
+        }
+    }
+
+    #[test]
+    fn ordinary_code_blocks_are_not_examples() {
+        let docs = "```text\ninput:\n1721\nexpected:\n514579\n```";
+
+        assert!(parse_examples(docs).is_empty());
+    }
+}
@@ -0,0 +1,205 @@
+use anyhow::Error;
+use aoc_core::{Answer, GroupedLines, Lines};
+use std::str::FromStr;
+
+/// Day 1: Sum Of Numbers
+///
+/// # Description
+///
+/// Add up all the numbers.
+///
+/// # Examples
+///
+/// ```example
+/// input:
+/// 1
+/// 2
+/// 3
+/// expected:
+/// 6
+/// ```
+///
+/// ```example
+/// input:
+/// 40
+/// 2
+/// expected:
+/// 42
+/// ```
+#[aoc_macros::challenge]
+pub fn sum(numbers: Lines<u32>) -> Result<u32, Error> {
+    Ok(numbers.iter().sum())
+}
+
+/// Day 2: Product Of Numbers
+///
+/// # Description
+///
+/// Multiply all the numbers.
+#[aoc_macros::challenge(
+    tags("math"),
+    example(input = "1\n2\n3", expected = "6"),
+    example(input = "6\n7", expected = "42")
+)]
+pub fn product(numbers: Lines<u32>) -> Result<u32, Error> {
+    Ok(numbers.iter().product())
+}
+
+/// A type which can only be parsed using [`FromStr`].
+#[derive(Debug)]
+pub struct Total(u32);
+
+impl FromStr for Total {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let numbers: Lines<u32> = s.parse()?;
+        Ok(Total(numbers.iter().sum()))
+    }
+}
+
+/// Day 3: Parsed With FromStr
+#[aoc_macros::challenge(
+    parse_with = "FromStr",
+    example(input = "1\n2", expected = "3")
+)]
+pub fn total(total: Total) -> Result<u32, Error> {
+    Ok(total.0)
+}
+
+/// Day 4: Borrowed Input
+#[aoc_macros::challenge(example(input = "a\nb\n\nc", expected = "a b"))]
+pub fn first_group(groups: GroupedLines<'_>) -> Result<String, Error> {
+    groups
+        .map(|group| group.as_str_with_spaces())
+        .next()
+        .ok_or_else(|| Error::msg("No groups"))
+}
+
+/// An answer which doesn't implement `Display`.
+pub struct Sorted(Vec<u32>);
+
+impl Answer for Sorted {
+    fn format(&self) -> String {
+        let numbers: Vec<_> = self.0.iter().map(|n| n.to_string()).collect();
+        numbers.join(",")
+    }
+}
+
+/// Day 5: Custom Answer
+#[aoc_macros::challenge(example(input = "3\n1\n2", expected = "1,2,3"))]
+pub fn sorted(numbers: Lines<u32>) -> Result<Sorted, Error> {
+    let mut numbers = numbers.0;
+    numbers.sort();
+    Ok(Sorted(numbers))
+}
+
+/// Day 6: Count The Lines
+#[aoc_macros::challenge]
+pub fn count_lines(lines: Lines<String>) -> Result<usize, Error> {
+    Ok(lines.0.len())
+}
+
+/// Day 6: Count The Lines Again
+#[aoc_macros::challenge(year = 2021)]
+pub fn count_lines_again(lines: Lines<String>) -> Result<usize, Error> {
+    Ok(lines.0.len())
+}
+
+#[test]
+fn examples_are_attached_to_the_challenge() {
+    let challenge = aoc_core::all_challenges()
+        .find(|c| c.number == "1")
+        .unwrap();
+
+    assert_eq!(challenge.name, "Sum Of Numbers");
+    assert_eq!(challenge.description, "Add up all the numbers.");
+    assert_eq!(challenge.example_count(), 2);
+    assert_eq!(challenge.examples[0].input, "1\n2\n3");
+    assert_eq!(challenge.examples[0].expected, "6");
+    assert_eq!(challenge.examples[1].input, "40\n2");
+    assert_eq!(challenge.examples[1].expected, "42");
+
+    for example in challenge.examples {
+        let got = (challenge.solve)(example.input).unwrap();
+        assert_eq!(got, example.expected);
+    }
+}
+
+#[test]
+fn inputs_can_be_validated_without_solving() {
+    let challenge = aoc_core::all_challenges()
+        .find(|c| c.number == "1")
+        .unwrap();
+
+    assert!((challenge.validate)("1\n2\n3").is_ok());
+    assert!((challenge.validate)("1\nx\n3").is_err());
+}
+
+#[test]
+fn examples_can_be_passed_to_the_attribute() {
+    let challenge = aoc_core::all_challenges()
+        .find(|c| c.number == "2")
+        .unwrap();
+
+    assert_eq!(challenge.tags, &["math"]);
+    assert_eq!(challenge.examples.len(), 2);
+    assert_eq!(challenge.examples[1].input, "6\n7");
+    assert_eq!(challenge.examples[1].expected, "42");
+
+    for example in challenge.examples {
+        let got = (challenge.solve)(example.input).unwrap();
+        assert_eq!(got, example.expected);
+    }
+}
+
+#[test]
+fn inputs_can_be_parsed_in_different_ways() {
+    for number in &["3", "4"] {
+        let challenge = aoc_core::all_challenges()
+            .find(|c| c.number == *number)
+            .unwrap();
+
+        for example in challenge.examples {
+            let got = (challenge.solve)(example.input).unwrap();
+            assert_eq!(got, example.expected);
+        }
+    }
+}
+
+#[test]
+fn challenges_can_return_custom_answers() {
+    let challenge = aoc_core::all_challenges()
+        .find(|c| c.number == "5")
+        .unwrap();
+
+    let got = (challenge.solve)("10\n5\n7").unwrap();
+
+    assert_eq!(got, "5,7,10");
+}
+
+#[test]
+fn solve_from_bytes_falls_back_to_text() {
+    let challenge = aoc_core::all_challenges()
+        .find(|c| c.number == "1")
+        .unwrap();
+
+    assert!(!challenge.accepts_bytes);
+    assert_eq!((challenge.solve_bytes)(b"1\n2\n3").unwrap(), "6");
+    assert!((challenge.solve_bytes)(b"1\n\xff\n3").is_err());
+}
+
+#[test]
+fn the_same_day_can_be_used_in_different_years() {
+    let mut years: Vec<_> = aoc_core::all_challenges()
+        .filter(|c| c.number == "6")
+        .map(|c| (c.year, c.name))
+        .collect();
+    years.sort();
+
+    assert_eq!(
+        years,
+        vec![(2020, "Count The Lines"), (2021, "Count The Lines Again")]
+    );
+    aoc_core::validate_unique_challenges().unwrap();
+}